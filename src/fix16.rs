@@ -1,607 +1,939 @@
-use std::{ops, u16, u32};
+use crate::fixed::{isqrt_rem, Fixed, ParseFixedError};
+use crate::rounding::{div_mode, mul_mode, round_div, round_inexact, round_sqrt, RoundingMode};
 
-#[derive(Copy, Clone, Debug)]
-pub struct Fix16(pub i32);
+pub type Fix16 = Fixed<16>;
+
+// `Fix16` is an alias, so this stands in for the tuple struct constructor.
+#[allow(non_snake_case)]
+pub const fn Fix16(bits: i32) -> Fix16 {
+    Fixed(bits)
+}
+
+pub type ParseFix16Error = ParseFixedError;
+
+// Converts a decimal literal to a Fix16 at compile time, rounding exactly like
+// parsing does. Takes either a string or a numeric literal, e.g.
+// `fix16!("1.2345")` or `fix16!(-1.2345)`, and fails to compile if the value
+// is out of range or not a number.
+#[macro_export]
+macro_rules! fix16 {
+    ($lit:literal) => {{
+        const VALUE: $crate::fix16::Fix16 = $crate::fix16::from_literal(stringify!($lit));
+        VALUE
+    }};
+}
+
+#[doc(hidden)]
+pub const fn from_literal(lit: &str) -> Fix16 {
+    let mut bytes = lit.as_bytes();
+    let mut buffer = [0u8; 64];
+
+    // String literals are stringified with their quotes, while numeric
+    // literals may contain `_` digit separators.
+    if let Some((b'"', rest)) = bytes.split_first() {
+        if let Some((b'"', rest)) = rest.split_last() {
+            bytes = rest;
+        }
+    } else if contains_separator(bytes) {
+        let mut len = 0;
+        let mut pos = 0;
+
+        while pos < bytes.len() {
+            if bytes[pos] != b'_' {
+                if len == buffer.len() {
+                    panic!("fix16! literal is too long");
+                }
+                buffer[len] = bytes[pos];
+                len += 1;
+            }
+            pos += 1;
+        }
+
+        bytes = buffer.split_at(len).0;
+    }
+
+    match Fix16::from_bytes(bytes) {
+        Ok(val) => val,
+        Err(ParseFixedError::Overflow) => panic!("fix16! literal out of range"),
+        Err(ParseFixedError::Empty) => panic!("fix16! literal is empty"),
+        Err(_) => panic!("fix16! literal is not a number"),
+    }
+}
+
+const fn contains_separator(bytes: &[u8]) -> bool {
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        if bytes[pos] == b'_' {
+            return true;
+        }
+        pos += 1;
+    }
+
+    false
+}
 
 pub const FOUR_DIV_PI: Fix16 = Fix16(0x145F3);
 pub const NEG_FOUR_DIV_PI: Fix16 = Fix16(-0x67c0);
 pub const X4_CORRECTION_COMPONENT: Fix16 = Fix16(0x399A);
 pub const PI_DIV_4: Fix16 = Fix16(0x0000C90F);
 pub const THREE_PI_DIV_4: Fix16 = Fix16(0x00025B2F);
+pub const LN_2: Fix16 = Fix16(45426);
+const LN_2_POW_16: Fix16 = Fix16(726817);
+
+#[deprecated(note = "use Fix16::MAX")]
+pub const FIX16_MAX: Fix16 = Fix16::MAX;
+#[deprecated(note = "use Fix16::MIN")]
+pub const FIX16_MIN: Fix16 = Fix16::MIN;
+// libfixmath's error value, which is also a valid result.
+pub const FIX16_OVERFLOW: Fix16 = Fix16::MIN;
+
+#[deprecated(note = "use Fix16::PI")]
+pub const FIX16_PI: Fix16 = Fix16::PI;
+#[deprecated(note = "use Fix16::E")]
+pub const FIX16_E: Fix16 = Fix16::E;
+#[deprecated(note = "use Fix16::ONE")]
+pub const FIX16_ONE: Fix16 = Fix16::ONE;
 
-pub const FIX16_MAX: Fix16 = Fix16(0x7FFFFFFF);
-pub const FIX16_MIN: Fix16 = Fix16(-0x80000000);
-pub const FIX16_OVERFLOW: Fix16 = FIX16_MIN;
+impl Fix16 {
+    // The equivalent of libfixmath's fix16_to_str, with `decimals` places.
+    // Fails if `buf` is too short.
+    pub fn to_str_buf(self, buf: &mut [u8], decimals: usize) -> Result<&str, core::fmt::Error> {
+        crate::format::format_into(buf, format_args!("{:.*}", decimals, self))
+    }
 
-pub const FIX16_PI: Fix16 = Fix16(205887);
-pub const FIX16_E: Fix16 = Fix16(178145);
-pub const FIX16_ONE: Fix16 = Fix16(0x00010000);
+    #[cfg(feature = "std")]
+    pub fn to_str(self, decimals: usize) -> String {
+        format!("{:.*}", decimals, self)
+    }
 
-impl From<i32> for Fix16 {
-    fn from(val: i32) -> Self {
-        Fix16(val * FIX16_ONE.0)
+    pub fn sin(self) -> Fix16 {
+        Fix16(sin_reduced(self.reduce_angle()))
     }
-}
 
-impl From<f32> for Fix16 {
-    fn from(val: f32) -> Self {
-        Fix16({
-            let mut x = val * FIX16_ONE.0 as f32;
+    pub fn cos(self) -> Fix16 {
+        Fix16(self.0.wrapping_add(Fix16::PI.0 >> 1)).sin()
+    }
 
-            if !crate::no_rounding() {
-                x += if x.is_sign_positive() { 0.5 } else { -0.5 };
-            }
+    // The same results as sin and cos, but reducing the angle only once.
+    pub fn sin_cos(self) -> (Fix16, Fix16) {
+        let quarter = Fix16::PI.0 >> 1;
 
-            x as i32
-        })
+        // cos shifts the angle before reducing it, which only gives the same
+        // result as shifting the reduced angle if the shift does not wrap.
+        if self.0.checked_add(quarter).is_none() {
+            return (self.sin(), self.cos());
+        }
+
+        let angle = self.reduce_angle();
+        let mut shifted = angle + quarter;
+        if shifted >= Fix16::PI.0 << 1 {
+            shifted -= Fix16::PI.0 << 1;
+        }
+
+        (Fix16(sin_reduced(angle)), Fix16(sin_reduced(shifted)))
+    }
+
+    // sin and cos without the range reduction, for angles already in
+    // [0, π/2]. Other angles give wrong results or panic, and are caught by a
+    // debug assertion.
+    pub fn sin_unchecked(self) -> Fix16 {
+        debug_assert!(
+            self.0 >= 0 && self.0 <= Fix16::PI.0 >> 1,
+            "angle out of range"
+        );
+        Fix16(sin_lut(self.0))
     }
-}
 
-impl From<Fix16> for i32 {
-    fn from(val: Fix16) -> Self {
-        if crate::no_rounding() {
-            val.0 >> 16
-        } else if val.0 >= 0 {
-            (val.0 + (FIX16_ONE.0 >> 1)) / FIX16_ONE.0
+    pub fn cos_unchecked(self) -> Fix16 {
+        debug_assert!(
+            self.0 >= 0 && self.0 <= Fix16::PI.0 >> 1,
+            "angle out of range"
+        );
+        Fix16(sin_lut(self.0 + (Fix16::PI.0 >> 1)))
+    }
+
+    // The angle modulo 2π, in [0, 2π).
+    fn reduce_angle(self) -> i32 {
+        let two_pi = Fix16::PI.0 << 1;
+        let angle = self.0 % two_pi;
+
+        if angle < 0 {
+            angle + two_pi
         } else {
-            (val.0 - (FIX16_ONE.0 >> 1)) / FIX16_ONE.0
+            angle
         }
     }
-}
 
-impl From<Fix16> for f32 {
-    fn from(val: Fix16) -> Self {
-        val.0 as f32 / FIX16_ONE.0 as f32
+    pub fn tan(self) -> Fix16 {
+        let (sin, cos) = self.sin_cos();
+        sin.saturating_div(cos)
     }
-}
 
-impl Fix16 {
-    pub fn abs(self) -> Fix16 {
-        if self.0.is_negative() {
-            Fix16(-self.0)
-        } else {
-            self
+    pub fn asin(self) -> Fix16 {
+        if self.0 > Fix16::ONE.0 || self.0 < -Fix16::ONE.0 {
+            return Fix16(0);
         }
+
+        let out = Fix16::ONE - self * self;
+        self.sentinel_div(out.sqrt()).atan()
     }
 
-    pub fn floor(self) -> Fix16 {
-        Fix16(self.0 & -0x10000)
+    pub fn acos(self) -> Fix16 {
+        Fix16((Fix16::PI.0 >> 1) - self.asin().0)
     }
 
-    pub fn ceil(self) -> Fix16 {
-        Fix16(
-            (self.0 & -0x10000)
-                + if (self.0 & 0xFFFF) != 0 {
-                    FIX16_ONE.0
-                } else {
-                    0
-                },
-        )
+    pub fn atan(self) -> Fix16 {
+        self.atan2(Fix16::ONE)
     }
 
-    pub fn min(self, rhs: Fix16) -> Fix16 {
-        if self.0 <= rhs.0 {
-            self
+    // libfixmath evaluates these with plain C int arithmetic, so the
+    // intermediate sums are wrapping to stay bit-identical for extreme inputs.
+    pub fn atan2(self, x: Fix16) -> Fix16 {
+        let mask = self.0 >> 31;
+        let abs_y = self.0.wrapping_add(mask) ^ mask;
+
+        let (r, offset) = if x.0 >= 0 {
+            (
+                Fix16(x.0.wrapping_sub(abs_y)).sentinel_div(Fix16(x.0.wrapping_add(abs_y))),
+                PI_DIV_4,
+            )
         } else {
-            rhs
-        }
-    }
+            (
+                Fix16(x.0.wrapping_add(abs_y)).sentinel_div(Fix16(abs_y.wrapping_sub(x.0))),
+                THREE_PI_DIV_4,
+            )
+        };
 
-    pub fn max(self, rhs: Fix16) -> Fix16 {
-        if self.0 >= rhs.0 {
-            self
+        let r_3 = r.sentinel_mul(r).sentinel_mul(r);
+        let angle = Fix16(0x3240)
+            .sentinel_mul(r_3)
+            .0
+            .wrapping_sub(Fix16(0xFB50).sentinel_mul(r).0)
+            .wrapping_add(offset.0);
+
+        if self.0 < 0 {
+            Fix16(angle.wrapping_neg())
         } else {
-            rhs
+            Fix16(angle)
         }
     }
 
-    pub fn clamp(self, low: Fix16, high: Fix16) -> Fix16 {
-        self.min(low).max(high)
-    }
+    // The hyperbolic functions are built on exp, ln and sqrt. Out of range
+    // results saturate to Fix16::MAX or Fix16::MIN instead of returning
+    // FIX16_OVERFLOW. Error bounds are versus f64 over every input.
 
-    pub fn overflowing_add(self, rhs: Fix16) -> (Fix16, bool) {
-        let res = self.0.overflowing_add(rhs.0);
-        (Fix16(res.0), res.1)
+    // Within 3 ulps for results up to 1 in magnitude, and 0.004% above.
+    pub fn sinh(self) -> Fix16 {
+        let res = self.half_exp_sum(true);
+
+        match (self.0 < 0, res == Fix16::MAX) {
+            (true, true) => Fix16::MIN,
+            (true, false) => -res,
+            (false, _) => res,
+        }
     }
 
-    pub fn overflowing_sub(self, rhs: Fix16) -> (Fix16, bool) {
-        let res = self.0.overflowing_sub(rhs.0);
-        (Fix16(res.0), res.1)
+    // Within 0.003%.
+    pub fn cosh(self) -> Fix16 {
+        self.half_exp_sum(false)
     }
 
-    pub fn overflowing_mul(self, rhs: Fix16) -> (Fix16, bool) {
-        let mut overflowed = false;
-        let mut product = self.0 as i64 * rhs.0 as i64;
+    // (e^|x| + e^-|x|) / 2, or the difference, saturating.
+    fn half_exp_sum(self, difference: bool) -> Fix16 {
+        // Both are out of range long before |x| = 12.
+        let x = self.0.unsigned_abs().min(12 << 16) as i32;
 
-        let upper = (product >> 47) as u32;
-        if product < 0 {
-            if !upper != 0 {
-                overflowed = true;
-            }
+        if x < 8 << 16 {
+            // Halving once at the end is more accurate, and exact for x = 0.
+            let pos = Fix16(x).exp().0;
+            let neg = Fix16(-x).exp().0;
+            let sum = if difference { pos - neg } else { pos + neg };
 
             if crate::no_rounding() {
-                product -= 1;
+                Fix16(sum >> 1)
+            } else {
+                Fix16((sum + 1) >> 1)
             }
-        } else if upper != 0 {
-            overflowed = true;
-        }
-
-        let res = {
-            let mut res = (product >> 16) as i32;
+        } else {
+            // e^x alone would overflow, so halve the exponentials first.
+            let pos = Fix16(x - LN_2.0).saturating_exp();
+            let neg = Fix16(-x - LN_2.0).saturating_exp();
 
-            if !crate::no_rounding() {
-                res += ((product & 0x8000) >> 15) as i32;
+            if difference {
+                pos.saturating_sub(neg)
+            } else {
+                pos.saturating_add(neg)
             }
+        }
+    }
 
-            Fix16(res)
+    // Within 2 ulps.
+    pub fn tanh(self) -> Fix16 {
+        let x = self.0.unsigned_abs();
+
+        // Beyond this the result rounds to ±1.
+        let res = if x >= 0x6_4000 {
+            Fix16::ONE
+        } else {
+            let t = Fix16(-2 * x as i32).saturating_exp();
+            (Fix16::ONE - t) / (Fix16::ONE + t)
         };
 
-        (res, overflowed)
+        if self.0 < 0 {
+            -res
+        } else {
+            res
+        }
     }
 
-    pub fn overflowing_div(self, rhs: Fix16) -> (Fix16, bool) {
-        if rhs.0 == 0 {
-            return (FIX16_MIN, false);
+    // Within 3 ulps for results up to 1 in magnitude, and 0.004% above.
+    pub fn asinh(self) -> Fix16 {
+        let x = self.checked_abs().unwrap_or(Fix16::MAX);
+        let res = if x.0 > 181 << 16 {
+            // x^2 would overflow, and asinh(x) = ln(2x) to within half an ulp.
+            x.ln() + LN_2
+        } else {
+            (x + (x * x + Fix16::ONE).sqrt()).ln()
+        };
+
+        if self.0 < 0 {
+            -res
+        } else {
+            res
         }
+    }
 
-        let mut remainder = self.0.abs() as u32;
-        let mut divider = rhs.0.abs() as u32;
-        let mut quotient: u32 = 0;
-        let mut bit_pos = 17i32;
-        let mut overflowed = false;
+    // Within 4 ulps for results up to 1, and 0.004% above. Inputs below 1 are
+    // clamped to 1, giving 0.
+    pub fn acosh(self) -> Fix16 {
+        if self.0 <= Fix16::ONE.0 {
+            Fix16::ZERO
+        } else if self.0 > 181 << 16 {
+            self.ln() + LN_2
+        } else {
+            // x^2 - 1 without the cancellation.
+            (self + ((self - Fix16::ONE) * (self + Fix16::ONE)).sqrt()).ln()
+        }
+    }
+
+    // Within 2 ulps for results up to 1 in magnitude, and 0.002% above.
+    // Saturates to Fix16::MAX or Fix16::MIN from ±1 outwards.
+    pub fn atanh(self) -> Fix16 {
+        if self.0 >= Fix16::ONE.0 {
+            return Fix16::MAX;
+        } else if self.0 <= -Fix16::ONE.0 {
+            return Fix16::MIN;
+        }
+
+        // atanh is odd, and for x >= 0 the quotient below is never small enough
+        // to lose precision to rounding.
+        let x = self.abs();
+        let num = Fix16::ONE + x;
+        let den = Fix16::ONE - x;
+        let ln = match num.checked_div(den) {
+            Some(quotient) => quotient.ln(),
+            // Within 4 ulps of 1, where ln is inaccurate for the tiny
+            // denominator, so take it as ln(bits) - ln(2^16).
+            None => num.ln() - Fix16::from(den.0).ln() + LN_2_POW_16,
+        };
+        let res = ln * Fix16(0x8000);
 
-        // Kick-start the division a bit.
-        // This improves speed in the worst-case scenarios where N and D are large
-        // It gets a lower estimate for the result by N/(D >> 17 + 1).
-        if (divider & 0xFFF0_0000) != 0 {
-            let shifted_div = (divider >> 17) + 1;
-            quotient = remainder / shifted_div;
-            remainder -= ((quotient as u64 * divider as u64) >> 17) as u32;
+        if self.0 < 0 {
+            -res
+        } else {
+            res
         }
+    }
 
-        // If the divider is divisible by 2^n, take advantage of it.
-        while (divider & 0xF) == 0 && bit_pos >= 4 {
-            divider >>= 4;
-            bit_pos -= 4;
+    pub fn exp(self) -> Fix16 {
+        if self.0 == 0 {
+            return Fix16::ONE;
+        } else if self.0 == Fix16::ONE.0 {
+            return Fix16::E;
+        } else if self.0 >= 681391 {
+            return Fix16::MAX;
+        } else if self.0 <= -772243 {
+            return Fix16(0);
         }
 
-        while remainder != 0 && bit_pos >= 0 {
-            let shift = remainder.leading_zeros().min(bit_pos as u32);
+        // The power series converges much faster on positive values, and
+        // exp(-x) = 1/exp(x).
+        let negative = self.0 < 0;
+        let value = self.abs();
+
+        let mut res = value.0 + Fix16::ONE.0;
+        let mut term = value;
 
-            remainder <<= shift;
-            bit_pos -= shift as i32;
+        for i in 2..30 {
+            term = term.sentinel_mul(value.sentinel_div(Fix16::from(i)));
+            res = res.wrapping_add(term.0);
 
-            let div = remainder / divider;
-            remainder = remainder % divider;
-            quotient += div << bit_pos;
+            if term.0 < 500 && (i > 15 || term.0 < 20) {
+                break;
+            }
+        }
 
-            overflowed |= div & !(u32::MAX >> bit_pos) != 0;
+        if negative {
+            Fix16::ONE.sentinel_div(Fix16(res))
+        } else {
+            Fix16(res)
+        }
+    }
 
-            remainder <<= 1;
-            bit_pos -= 1;
+    pub fn saturating_exp(self) -> Fix16 {
+        // Below -ln(Fix16::MAX) the series sum in exp wraps before being
+        // inverted, so square the exponential of the half angle instead.
+        if self.0 < -681391 && self.0 > -772243 {
+            let half = Fix16(self.0 / 2).exp();
+            half * half
+        } else {
+            self.exp()
         }
+    }
 
-        if !crate::no_rounding() {
-            quotient += 1;
+    pub fn ln(self) -> Fix16 {
+        if self.0 <= 0 {
+            return FIX16_OVERFLOW;
         }
 
-        let res = quotient as i32 >> 1;
+        // Bring the value to the most accurate range (1 < x < 100).
+        let e_to_fourth = Fix16(3578144);
+        let mut value = self;
+        let mut scaling = 0;
 
-        if ((self.0 as u32 ^ rhs.0 as u32) & 0x80000000) != 0 {
-            overflowed |= res == FIX16_MIN.0;
+        while value.0 > Fix16::from(100).0 {
+            value /= e_to_fourth;
+            scaling += 4;
+        }
 
-            (Fix16(-res), overflowed)
-        } else {
-            (Fix16(res), overflowed)
+        while value.0 < Fix16::ONE.0 {
+            value *= e_to_fourth;
+            scaling -= 4;
         }
-    }
 
-    pub fn saturating_add(self, rhs: Fix16) -> Fix16 {
-        let res = self.0.overflowing_add(rhs.0);
-        if res.1 {
-            if self.0 >= 0 {
-                FIX16_MAX
-            } else {
-                FIX16_MIN
+        // Solve exp(guess) = value using Newton's method.
+        let mut guess = Fix16::from(2);
+        let mut count = 0;
+
+        loop {
+            let e = guess.exp();
+            let delta = Fix16(value.0 - e.0).sentinel_div(e).min(Fix16::from(3));
+
+            guess = guess.sentinel_add(delta);
+
+            count += 1;
+            if count > 10 || (delta.0 <= 1 && delta.0 >= -1) {
+                break;
             }
-        } else {
-            Fix16(res.0)
         }
+
+        guess.sentinel_add(Fix16::from(scaling))
     }
 
-    pub fn saturating_sub(self, rhs: Fix16) -> Fix16 {
-        let res = self.0.overflowing_sub(rhs.0);
-        if res.1 {
-            if self.0 >= 0 {
-                FIX16_MAX
-            } else {
-                FIX16_MIN
-            }
+    pub fn saturating_ln(self) -> Fix16 {
+        if self.0 <= 0 {
+            Fix16::MIN
         } else {
-            Fix16(res.0)
+            self.ln()
         }
     }
 
-    pub fn saturating_mul(self, rhs: Fix16) -> Fix16 {
-        let res = self.overflowing_mul(rhs);
+    pub fn log2(self) -> Fix16 {
+        if self.0 <= 0 {
+            return FIX16_OVERFLOW;
+        }
 
-        if res.1 {
-            if self.0.is_positive() == rhs.0.is_positive() {
-                FIX16_MAX
-            } else {
-                FIX16_MIN
+        // If the input is less than one, the result is -log2(1.0 / self).
+        if self.0 < Fix16::ONE.0 {
+            // The inverse of this would overflow, so return the exact answer.
+            if self.0 == 1 {
+                return Fix16::from(-16);
             }
-        } else {
-            res.0
+
+            return Fix16(-Fix16::ONE.sentinel_div(self).log2_inner().0);
         }
+
+        self.log2_inner()
     }
 
-    pub fn saturating_div(self, rhs: Fix16) -> Fix16 {
-        let res = self.overflowing_div(rhs);
+    pub fn saturating_log2(self) -> Fix16 {
+        let res = self.log2();
 
-        if res.1 {
-            if self.0.is_positive() == rhs.0.is_positive() {
-                FIX16_MAX
-            } else {
-                FIX16_MIN
-            }
+        // The only possible overflow is a non-positive input.
+        if res == FIX16_OVERFLOW {
+            Fix16::MIN
         } else {
-            res.0
+            res
         }
     }
 
-    pub fn from_hex_str(str: &String) -> Result<Fix16, String> {
-        let chars = str.chars().collect::<Vec<char>>();
-        let negative = chars[0] == '-';
-        let start_offset = {
-            let off_one = if negative { 1 } else { 0 };
-            let off_two = if chars.len() > off_one
-                && chars[off_one] == '0'
-                && chars.len() > off_one + 1
-                && (chars[off_one + 1] == 'x' || chars[off_one + 1] == 'X')
-            {
-                2
+    // Assumes self >= 1, so the result is always less than the input.
+    fn log2_inner(self) -> Fix16 {
+        fn rs(x: i32) -> i32 {
+            if crate::no_rounding() {
+                x >> 1
             } else {
-                0
-            };
-            off_one + off_two
-        };
+                (x >> 1) + (x & 1)
+            }
+        }
 
-        fn is_num_str(inp: &String) -> bool {
-            let chars = inp.chars().collect::<Vec<char>>();
-            for x in 0..inp.len() {
-                if !(chars[x] >= '0' && chars[x] <= '9') {
-                    return false;
-                }
+        let two = Fix16::from(2);
+        let mut x = self;
+        let mut res = 0;
+
+        while x.0 >= two.0 {
+            res += 1;
+            x = Fix16(rs(x.0));
+        }
+
+        if x.0 == 0 {
+            return Fix16(res << 16);
+        }
+
+        for _ in 0..16 {
+            x = x.sentinel_mul(x);
+            res <<= 1;
+
+            if x.0 >= two.0 {
+                res |= 1;
+                x = Fix16(rs(x.0));
             }
-            true
-        }
-
-        let contains_dec = str.find('.');
-
-        let out_val: u32;
-
-        if let Some(dec_point_offset) = contains_dec {
-            let pre_dec_str = chars[start_offset..dec_point_offset]
-                .iter()
-                .collect::<String>();
-            let mut post_dec_str = chars[dec_point_offset + 1..chars.len()]
-                .iter()
-                .collect::<String>();
-            let post_dec_str_len = post_dec_str.len();
-            if post_dec_str_len < 4 && is_num_str(&post_dec_str) {
-                if post_dec_str_len == 1 {
-                    post_dec_str = format!("0{}00", post_dec_str);
-                } else if post_dec_str_len == 2 {
-                    post_dec_str = format!("{}00", post_dec_str);
-                } else {
-                    post_dec_str = format!("{}0", post_dec_str);
-                }
+        }
+
+        if !crate::no_rounding() {
+            x = x.sentinel_mul(x);
+            if x.0 >= two.0 {
+                res += 1;
             }
+        }
 
-            let pre_dec_parsed = u16::from_str_radix(&pre_dec_str, 16);
-            let post_dec_parsed = u16::from_str_radix(&post_dec_str, 16);
+        Fix16(res)
+    }
 
-            if let Err(why) = pre_dec_parsed {
-                return Err(why.to_string());
-            } else if let Err(why) = post_dec_parsed {
-                return Err(why.to_string());
-            }
+    pub fn pow(self, exp: Fix16) -> Fix16 {
+        self.pow_inner(exp).unwrap_or(FIX16_OVERFLOW)
+    }
 
-            out_val = (pre_dec_parsed.unwrap() as u32) << 16 | (post_dec_parsed.unwrap() as u32);
-        } else {
-            let pre_dec_str = chars[start_offset..chars.len()].iter().collect::<String>();
-            let pre_dec_parsed = u16::from_str_radix(&pre_dec_str, 16);
+    pub fn saturating_pow(self, exp: Fix16) -> Fix16 {
+        match self.pow_inner(exp) {
+            Some(res) => res,
+            None if self.0 < 0 && exp.0 & 0xFFFF == 0 && exp.0 & Fix16::ONE.0 == 0 => Fix16::MAX,
+            None if self.0 < 0 => Fix16::MIN,
+            None => Fix16::MAX,
+        }
+    }
 
-            if let Err(why) = pre_dec_parsed {
-                return Err(why.to_string());
-            }
+    // Returns None when the result overflows or is not a real number.
+    fn pow_inner(self, exp: Fix16) -> Option<Fix16> {
+        if self.0 == 0 {
+            return match exp.0 {
+                0 => Some(Fix16::ONE),
+                e if e > 0 => Some(Fix16(0)),
+                _ => None,
+            };
+        }
 
-            out_val = (pre_dec_parsed.unwrap() as u32) << 16;
+        if exp.0 & 0xFFFF == 0 {
+            return self.powi_inner(exp.0 >> 16);
         }
 
-        if negative {
-            Ok(Fix16((out_val as i32) * -1))
+        // Negative bases only have real powers for integer exponents.
+        if self.0 < 0 {
+            return None;
+        }
+
+        let (power, overflowed) = exp.overflowing_mul(self.ln());
+        if overflowed {
+            return if (exp.0 < 0) == (self.0 < Fix16::ONE.0) {
+                None
+            } else {
+                Some(Fix16(0))
+            };
+        }
+
+        if power.0 >= 681391 {
+            None
         } else {
-            Ok(Fix16(out_val as i32))
+            Some(power.saturating_exp())
         }
     }
 
-    pub fn from_str(str: &String) -> Result<Fix16, String> {
-        let mut chars = str.chars();
-        let radix_indictator = [chars.next().unwrap_or(' '), chars.next().unwrap_or(' ')];
-        match radix_indictator {
-            ['0','x'] => {
-                Fix16::from_hex_str(str)
+    // Exponentiation by squaring, which avoids the error of going through ln.
+    // Each product is rounded like the operators, but kept wide so that the
+    // reciprocal for a negative exponent is taken from the true power.
+    fn powi_inner(self, exp: i32) -> Option<Fix16> {
+        // Far beyond any power whose reciprocal rounds to anything but zero,
+        // and small enough that products of two such values fit in an i128.
+        const HUGE: i128 = 1 << 62;
+
+        let mut n = exp.unsigned_abs();
+        let mut base = self.0 as i128;
+        let mut res = Fix16::ONE.0 as i128;
+
+        // Powers only grow once past HUGE, since the base is then above one.
+        while n != 0 && res.abs() <= HUGE && base.abs() <= HUGE {
+            if n & 1 != 0 {
+                res = round_div(res * base, 1 << 16, mul_mode());
             }
-            _ => {
-                match str.parse::<f32>() {
-                    Ok(c) => Ok(Fix16::from(c)),
-                    Err(why) => Err(why.to_string()),
-                }
+
+            n >>= 1;
+            if n != 0 {
+                base = round_div(base * base, 1 << 16, mul_mode());
             }
         }
-    }
 
-    pub fn sqrt(self) -> Fix16 {
-        let mut num = if self.0.is_negative() {
-            -self.0
-        } else {
-            self.0
-        } as u32;
-        let mut res = 0u32;
-        let mut bit;
+        let huge = n != 0 || res.abs() > HUGE;
 
-        if (num & 0xFFF00000) != 0 {
-            bit = 1u32 << 30;
+        if exp >= 0 {
+            if huge || res != res as i32 as i128 {
+                None
+            } else {
+                Some(Fix16(res as i32))
+            }
+        } else if huge {
+            Some(Fix16(0))
+        } else if res == 0 {
+            None
         } else {
-            bit = 1u32 << 18;
-        }
+            let recip = round_div_signed(1 << 32, res, div_mode());
 
-        while bit > num {
-            bit >>= 2;
+            if recip != recip as i32 as i128 {
+                None
+            } else {
+                Some(Fix16(recip as i32))
+            }
         }
+    }
 
-        for x in 0..2 {
-            while bit != 0 {
-                if num >= res + bit {
-                    num -= res + bit;
-                    res = (res >> 1) + bit;
-                } else {
-                    res >>= 1;
-                }
-                bit >>= 2;
-            }
+    // The fast variants below use Newton's method followed by an integer
+    // correction step, so they round to nearest like the operators (or
+    // truncate with `no-rounding`). The `_round` variants take a rounding mode
+    // and use exact integer algorithms instead. Results saturate.
 
-            if x == 0 {
-                if num > std::u16::MAX as u32 {
-                    num -= res;
-                    num = (num << 16) - 0x8000;
-                    res = (res << 16) + 0x8000;
-                } else {
-                    num <<= 16;
-                    res <<= 16;
-                }
+    pub fn recip(self) -> Fix16 {
+        if self.0 == 0 {
+            return Fix16::MAX;
+        }
 
-                bit = 1 << 14;
-            }
+        // 1 / x = 2^32 / bits, with bits normalised to m in [0.5, 1) as Q30.
+        let b = self.0.unsigned_abs() as u64;
+        let lz = b.leading_zeros() - 32;
+        let m = (b << lz) >> 2;
+
+        // 48/17 - 32/17 m is within 1/17 of 1 / m, and each step doubles the
+        // number of correct bits.
+        let mut r = 3031741621 - ((2021161081 * m) >> 30);
+        for _ in 0..3 {
+            r = (r * ((2 << 30) - ((m * r) >> 30))) >> 30;
         }
 
-        if !crate::no_rounding() && num > res {
-            res += 1;
+        let mut q = if lz > 30 { r << 1 } else { r >> (30 - lz) };
+        while q * b > 1 << 32 {
+            q -= 1;
+        }
+        while (q + 1) * b <= 1 << 32 {
+            q += 1;
+        }
+        if !crate::no_rounding() && 2 * ((1 << 32) - q * b) >= b {
+            q += 1;
         }
 
-        if self.0.is_negative() {
-            Fix16((res as i32) * -1)
-        } else {
-            Fix16(res as i32)
+        Fix16::saturate(q, self.0 < 0)
+    }
+
+    pub fn recip_round(self, mode: RoundingMode) -> Fix16 {
+        if self.0 == 0 {
+            return Fix16::MAX;
         }
+
+        let res = round_div(
+            (1i128 << 32) * self.0.signum() as i128,
+            self.0.unsigned_abs() as i128,
+            mode,
+        );
+        Fix16::saturate(res.unsigned_abs() as u64, res < 0)
     }
 
-    pub fn sin(self) -> Fix16 {
-        let shl_pi = Fix16(FIX16_PI.0 << 1);
-        let shr_pi = Fix16(FIX16_PI.0 >> 1);
-        let mut temp_angle = self % shl_pi;
-
-        if temp_angle.0 < 0 {
-            temp_angle += shl_pi;
-        }
-        
-        let out_val;
-        if temp_angle.0 >= FIX16_PI.0 {
-            temp_angle -= FIX16_PI;
-            if temp_angle.0 >= shr_pi.0 {
-                temp_angle = FIX16_PI - temp_angle;
-            }
+    // 1 / sqrt(x). Negative inputs are mirrored like sqrt, and zero gives
+    // Fix16::MAX.
+    pub fn rsqrt(self) -> Fix16 {
+        if self.0 == 0 {
+            return Fix16::MAX;
+        }
 
-            out_val = -(if temp_angle.0 >= crate::consts::F16_SIN_LUT_COUNT { FIX16_ONE.0 } else { crate::consts::F16_SIN_LUT[temp_angle.0 as usize] as i32 });
-        } else {
-            if temp_angle.0 >= shr_pi.0 {
-                temp_angle = FIX16_PI - temp_angle;
-            }
-            
-            out_val = if temp_angle.0 >= crate::consts::F16_SIN_LUT_COUNT { FIX16_ONE.0 } else { crate::consts::F16_SIN_LUT[temp_angle.0 as usize] as i32 };
+        // 1 / sqrt(x) = 2^24 / sqrt(bits), with bits normalised by an even
+        // shift to m in [0.25, 1) as Q30.
+        let b = self.0.unsigned_abs() as u64;
+        let shift = (b.leading_zeros() - 32) & !1;
+        let m = (b << shift) >> 2;
+
+        // Within 0.15 of 1 / sqrt(m), and Newton's method converges from there.
+        let mut y = 2362232013 - ((5 * m) >> 2);
+        for _ in 0..4 {
+            let y2 = (y * y) >> 30;
+            y = (y * ((3 << 30) - ((m * y2) >> 30))) >> 31;
         }
 
-        Fix16(out_val)
-    }
+        let b = b as u128;
+        let mut q = (y >> (22 - shift / 2)) as u128;
+        while q * q * b > 1 << 48 {
+            q -= 1;
+        }
+        while (q + 1) * (q + 1) * b <= 1 << 48 {
+            q += 1;
+        }
+        // The root is never exactly halfway.
+        if !crate::no_rounding() && (2 * q + 1) * (2 * q + 1) * b < 1 << 50 {
+            q += 1;
+        }
 
-    pub fn cos(self) -> Fix16 {
-        Fix16(self.0 + (FIX16_PI.0 >> 1)).sin()
+        Fix16::saturate(q as u64, self.0 < 0)
     }
 
-    pub fn tan(self) -> Fix16 {
-        self.sin().saturating_div(self.cos())
-    }
-}
+    pub fn rsqrt_round(self, mode: RoundingMode) -> Fix16 {
+        if self.0 == 0 {
+            return Fix16::MAX;
+        }
 
-impl ops::Add for Fix16 {
-    type Output = Self;
+        // The floor of the root of the floor of 2^48 / bits is exact.
+        let b = self.0.unsigned_abs() as u128;
+        let (floor, _) = isqrt_rem(((1u128 << 48) / b) as u64);
+        let square = floor as u128 * floor as u128 * b;
 
-    fn add(self, rhs: Self) -> Self::Output {
-        Fix16(self.0 + rhs.0)
-    }
-}
+        let res = if square == 1 << 48 {
+            floor
+        } else {
+            let odd = 2 * floor as u128 + 1;
+            let half = (1u128 << 50).cmp(&(odd * odd * b));
+            round_inexact(floor, half, self.0 < 0, mode)
+        };
 
-impl ops::AddAssign for Fix16 {
-    fn add_assign(&mut self, rhs: Self) {
-        *self = *self + rhs;
+        Fix16::saturate(res, self.0 < 0)
     }
-}
 
-impl ops::Sub for Fix16 {
-    type Output = Self;
+    pub fn cbrt(self) -> Fix16 {
+        // cbrt(x) = cbrt(bits * 2^32), starting above the root so that the
+        // integer Newton iteration decreases monotonically to its floor.
+        let n = (self.0.unsigned_abs() as u64) << 32;
+        let mut q = 1u64 << (64 - n.leading_zeros()).div_ceil(3);
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        Fix16(self.0 - rhs.0)
-    }
-}
+        while q != 0 {
+            let next = (2 * q + n / (q * q)) / 3;
+            if next >= q {
+                break;
+            }
+            q = next;
+        }
+
+        // The root is never exactly halfway.
+        if !crate::no_rounding() && (2 * q as u128 + 1).pow(3) < 8 * n as u128 {
+            q += 1;
+        }
 
-impl ops::SubAssign for Fix16 {
-    fn sub_assign(&mut self, rhs: Self) {
-        *self = *self - rhs;
+        Fix16::saturate(q, self.0 < 0)
     }
-}
 
-impl ops::Mul for Fix16 {
-    type Output = Self;
+    pub fn cbrt_round(self, mode: RoundingMode) -> Fix16 {
+        let n = (self.0.unsigned_abs() as u64) << 32;
+        let floor = icbrt(n);
+
+        let res = if floor.pow(3) == n {
+            floor
+        } else {
+            let half = (8 * n as u128).cmp(&(2 * floor as u128 + 1).pow(3));
+            round_inexact(floor, half, self.0 < 0, mode)
+        };
 
-    fn mul(self, rhs: Self) -> Self::Output {
-        let mut product = self.0 as i64 * rhs.0 as i64;
-        let upper = (product >> 47) as u32;
+        Fix16::saturate(res, self.0 < 0)
+    }
 
-        if product < 0 {
-            if !upper != 0 {
-                return FIX16_OVERFLOW;
-            }
+    // sqrt(x^2 + y^2). The squares are summed in 64 bits, so unlike going
+    // through sqrt nothing overflows before the result does.
+    pub fn hypot(self, y: Fix16) -> Fix16 {
+        let sum = Fix16::square_sum(self, y);
+        if sum == 0 {
+            return Fix16::ZERO;
+        }
 
-            if !crate::no_rounding() {
-                product -= 1;
+        let mut q = 1u64 << (64 - sum.leading_zeros()).div_ceil(2);
+        loop {
+            let next = (q + sum / q) / 2;
+            if next >= q {
+                break;
             }
-        } else if upper != 0 {
-            return FIX16_OVERFLOW;
+            q = next;
         }
 
-        if crate::no_rounding() {
-            Fix16((product >> 16) as i32)
-        } else {
-            Fix16(((product >> 16) + ((product & 0x8000) >> 15)) as i32)
+        if !crate::no_rounding() && sum - q * q > q {
+            q += 1;
         }
+
+        Fix16::saturate(q, false)
     }
-}
 
-impl ops::MulAssign for Fix16 {
-    fn mul_assign(&mut self, rhs: Self) {
-        *self = *self * rhs;
+    pub fn hypot_round(self, y: Fix16, mode: RoundingMode) -> Fix16 {
+        let (floor, rem) = isqrt_rem(Fix16::square_sum(self, y));
+        Fix16::saturate(round_sqrt(floor, rem, false, mode), false)
     }
-}
 
-impl ops::Div for Fix16 {
-    type Output = Self;
+    fn square_sum(x: Fix16, y: Fix16) -> u64 {
+        let (x, y) = (x.0.unsigned_abs() as u64, y.0.unsigned_abs() as u64);
+        x * x + y * y
+    }
 
-    // This uses a hardware 32/32 bit division multiple times, until we have
-    // computed all the bits in (a<<17)/b. Usually this takes 1-3 iterations.
-    fn div(self, rhs: Self) -> Self::Output {
-        if rhs.0 == 0 {
-            return FIX16_MIN;
+    fn saturate(magnitude: u64, negative: bool) -> Fix16 {
+        match (negative, magnitude) {
+            (false, m) if m > i32::MAX as u64 => Fix16::MAX,
+            (true, m) if m >= 1 << 31 => Fix16::MIN,
+            (false, m) => Fix16(m as i32),
+            (true, m) => Fix16(-(m as i32)),
         }
+    }
 
-        let mut remainder = self.0.abs() as u32;
-        let mut divider = rhs.0.abs() as u32;
-        let mut quotient: u32 = 0;
-        let mut bit_pos = 17i32;
+    // Linear interpolation from self to `to`. lerp8, lerp16 and lerp32 are
+    // libfixmath's, taking `fract / 2^N` as the fraction and rounding down.
+    //
+    // The `_precise` variants compute `(1 - t) * self + t * to` exactly and
+    // round once, so the endpoints are exact and nothing overflows. Their
+    // integer fractions are `fract / (2^N - 1)`, so that the largest one gives
+    // `to`.
 
-        // Kick-start the division a bit.
-        // This improves speed in the worst-case scenarios where N and D are large
-        // It gets a lower estimate for the result by N/(D >> 17 + 1).
-        if (divider & 0xFFF0_0000) != 0 {
-            let shifted_div = (divider >> 17) + 1;
-            quotient = remainder / shifted_div;
-            remainder -= ((quotient as u64 * divider as u64) >> 17) as u32;
-        }
+    // self + (to - self) * t with the operators, which can overflow when the
+    // endpoints are far apart.
+    pub fn lerp(self, to: Fix16, t: Fix16) -> Fix16 {
+        self + (to - self) * t
+    }
 
-        // If the divider is divisible by 2^n, take advantage of it.
-        while (divider & 0xF) == 0 && bit_pos >= 4 {
-            divider >>= 4;
-            bit_pos -= 4;
-        }
+    // Also extrapolates for t outside [0, 1], saturating.
+    pub fn lerp_precise(self, to: Fix16, t: Fix16) -> Fix16 {
+        self.lerp_exact(to, t.0 as i128, 1 << 16)
+    }
 
-        while remainder != 0 && bit_pos >= 0 {
-            let shift = remainder.leading_zeros().min(bit_pos as u32);
+    pub fn lerp8(self, to: Fix16, fract: u8) -> Fix16 {
+        let res = self.0 as i64 * (256 - fract as i64) + to.0 as i64 * fract as i64;
+        Fix16((res >> 8) as i32)
+    }
 
-            remainder <<= shift;
-            bit_pos -= shift as i32;
+    pub fn lerp8_precise(self, to: Fix16, fract: u8) -> Fix16 {
+        self.lerp_exact(to, fract as i128, u8::MAX as i128)
+    }
 
-            let div = remainder / divider;
-            remainder = remainder % divider;
-            quotient += div << bit_pos;
+    pub fn lerp16(self, to: Fix16, fract: u16) -> Fix16 {
+        let res = self.0 as i64 * (65536 - fract as i64) + to.0 as i64 * fract as i64;
+        Fix16((res >> 16) as i32)
+    }
 
-            remainder <<= 1;
-            bit_pos -= 1;
-        }
+    pub fn lerp16_precise(self, to: Fix16, fract: u16) -> Fix16 {
+        self.lerp_exact(to, fract as i128, u16::MAX as i128)
+    }
 
-        if !crate::no_rounding() {
-            quotient += 1;
+    pub fn lerp32(self, to: Fix16, fract: u32) -> Fix16 {
+        if fract == 0 {
+            return self;
         }
 
-        if ((self.0 as u32 ^ rhs.0 as u32) & 0x80000000) != 0 {
-            Fix16(-(quotient as i32 >> 1))
-        } else {
-            Fix16(quotient as i32 >> 1)
-        }
+        let res = self.0 as i64 * ((1 << 32) - fract as i64) + to.0 as i64 * fract as i64;
+        Fix16((res >> 32) as i32)
     }
-}
 
-impl ops::DivAssign for Fix16 {
-    fn div_assign(&mut self, rhs: Self) {
-        *self = *self / rhs;
+    pub fn lerp32_precise(self, to: Fix16, fract: u32) -> Fix16 {
+        self.lerp_exact(to, fract as i128, u32::MAX as i128)
     }
-}
 
-impl ops::Rem for Fix16 {
-    type Output = Self;
+    // The fraction at which `value` lies from self to `to`, the inverse of
+    // lerp. Divides by zero if the endpoints are equal.
+    pub fn inverse_lerp(self, to: Fix16, value: Fix16) -> Fix16 {
+        (value - self) / (to - self)
+    }
 
-    fn rem(self, rhs: Self) -> Self::Output {
-        Fix16(self.0 % rhs.0)
+    // Saturates, and gives zero if the endpoints are equal.
+    pub fn inverse_lerp_precise(self, to: Fix16, value: Fix16) -> Fix16 {
+        if self == to {
+            return Fix16::ZERO;
+        }
+
+        let num = (value.0 as i128 - self.0 as i128) << 16;
+        let den = to.0 as i128 - self.0 as i128;
+        Fix16::saturate_i128(round_div_signed(num, den, div_mode()))
     }
-}
 
-impl ops::RemAssign for Fix16 {
-    fn rem_assign(&mut self, rhs: Self) {
-        *self = *self % rhs;
+    // Maps self from the range `from` onto the range `to`, i.e.
+    // `to.0.lerp(to.1, from.0.inverse_lerp(from.1, self))`.
+    pub fn remap(self, from: (Fix16, Fix16), to: (Fix16, Fix16)) -> Fix16 {
+        to.0.lerp(to.1, from.0.inverse_lerp(from.1, self))
     }
-}
 
-impl ops::Shl for Fix16 {
-    type Output = Self;
+    // Rounds once instead of twice, and gives `to.0` for an empty `from`.
+    pub fn remap_precise(self, from: (Fix16, Fix16), to: (Fix16, Fix16)) -> Fix16 {
+        if from.0 == from.1 {
+            return to.0;
+        }
+
+        let span = to.1 .0 as i128 - to.0 .0 as i128;
+        let offset = (self.0 as i128 - from.0 .0 as i128) * span;
+        let res = round_div_signed(offset, from.1 .0 as i128 - from.0 .0 as i128, div_mode());
 
-    fn shl(self, rhs: Self) -> Self::Output {
-        Fix16(self.0 << i32::from(rhs))
+        Fix16::saturate_i128(to.0 .0 as i128 + res)
     }
-}
 
-impl ops::ShlAssign for Fix16 {
-    fn shl_assign(&mut self, rhs: Self) {
-        *self = *self << rhs;
+    // (1 - t) * self + t * to, where t = fract / one.
+    fn lerp_exact(self, to: Fix16, fract: i128, one: i128) -> Fix16 {
+        let num = self.0 as i128 * (one - fract) + to.0 as i128 * fract;
+        Fix16::saturate_i128(round_div_signed(num, one, mul_mode()))
+    }
+
+    fn saturate_i128(val: i128) -> Fix16 {
+        Fix16(val.max(i32::MIN as i128).min(i32::MAX as i128) as i32)
     }
 }
 
-impl ops::Shr for Fix16 {
-    type Output = Self;
+// Divides by a divisor of either sign, rounding the exact quotient with `mode`.
+fn round_div_signed(num: i128, den: i128, mode: RoundingMode) -> i128 {
+    if den < 0 {
+        round_div(-num, -den, mode)
+    } else {
+        round_div(num, den, mode)
+    }
+}
 
-    fn shr(self, rhs: Self) -> Self::Output {
-        Fix16(self.0 >> i32::from(rhs))
+// sin of an angle in [0, 2π).
+fn sin_reduced(angle: i32) -> i32 {
+    if angle >= Fix16::PI.0 {
+        -sin_lut(angle - Fix16::PI.0)
+    } else {
+        sin_lut(angle)
     }
 }
 
-impl ops::ShrAssign for Fix16 {
-    fn shr_assign(&mut self, rhs: Self) {
-        *self = *self >> rhs;
+// sin of an angle in [0, π), folded into the first quadrant of the table.
+fn sin_lut(mut angle: i32) -> i32 {
+    if angle >= Fix16::PI.0 >> 1 {
+        angle = Fix16::PI.0 - angle;
+    }
+
+    if angle >= crate::consts::F16_SIN_LUT_COUNT {
+        Fix16::ONE.0
+    } else {
+        crate::consts::F16_SIN_LUT[angle as usize] as i32
     }
 }
 
-impl PartialEq for Fix16 {
-    fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
+// The floor of the cube root, computed bit by bit.
+fn icbrt(mut num: u64) -> u64 {
+    let mut res = 0u64;
+    let mut shift = 63;
+
+    while shift >= 0 {
+        res <<= 1;
+        let bit = 3 * res * (res + 1) + 1;
+
+        if num >> shift >= bit {
+            num -= bit << shift;
+            res += 1;
+        }
+        shift -= 3;
     }
+
+    res
 }
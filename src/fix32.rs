@@ -0,0 +1,546 @@
+use core::{convert::TryFrom, fmt, ops, str::FromStr};
+
+use crate::fix16::Fix16;
+use crate::fixed::{ParseFixedError, TryFromFixedError};
+
+#[derive(Copy, Clone, Debug)]
+pub struct Fix32(pub i64);
+
+pub const FIX32_MAX: Fix32 = Fix32(0x7FFFFFFF_FFFFFFFF);
+pub const FIX32_MIN: Fix32 = Fix32(-0x80000000_00000000);
+pub const FIX32_OVERFLOW: Fix32 = FIX32_MIN;
+
+pub const FIX32_PI: Fix32 = Fix32(13493037705);
+pub const FIX32_E: Fix32 = Fix32(11674931555);
+pub const FIX32_ONE: Fix32 = Fix32(0x00000001_00000000);
+
+// pi/2 in Q34.92, so angles reduce without losing precision over the whole
+// Q32.32 range.
+const FRAC_PI_2_Q92: i128 = 0x1921fb54442d18469898cc51;
+
+impl fmt::Display for Fix32 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let magnitude = self.0.unsigned_abs();
+        crate::format::fmt_decimal(f, self.0 < 0, magnitude >> 32, magnitude & 0xFFFFFFFF, 32)
+    }
+}
+
+crate::format::radix_fmt_impls!([] Fix32, |val: Fix32| {
+    let magnitude = val.0.unsigned_abs();
+    (val.0 < 0, magnitude >> 32, magnitude & 0xFFFFFFFF, 32)
+});
+
+impl FromStr for Fix32 {
+    type Err = ParseFixedError;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        Fix32::from_bytes(str.as_bytes())
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Fix32 {
+    type Error = ParseFixedError;
+
+    fn try_from(str: &'a str) -> Result<Self, Self::Error> {
+        str.parse()
+    }
+}
+
+impl From<i32> for Fix32 {
+    fn from(val: i32) -> Self {
+        Fix32((val as i64) << 32)
+    }
+}
+
+#[cfg(feature = "float")]
+impl From<f32> for Fix32 {
+    fn from(val: f32) -> Self {
+        Fix32::from(val as f64)
+    }
+}
+
+#[cfg(feature = "float")]
+impl From<f64> for Fix32 {
+    fn from(val: f64) -> Self {
+        Fix32({
+            let mut x = val * FIX32_ONE.0 as f64;
+
+            if !crate::no_rounding() {
+                x += if x.is_sign_positive() { 0.5 } else { -0.5 };
+            }
+
+            x as i64
+        })
+    }
+}
+
+impl From<Fix16> for Fix32 {
+    fn from(val: Fix16) -> Self {
+        Fix32((val.0 as i64) << 16)
+    }
+}
+
+impl From<Fix32> for i64 {
+    fn from(val: Fix32) -> Self {
+        if crate::no_rounding() {
+            val.0 >> 32
+        } else if val.0 >= 0 {
+            ((val.0 as i128 + (FIX32_ONE.0 >> 1) as i128) >> 32) as i64
+        } else {
+            -((-(val.0 as i128) + (FIX32_ONE.0 >> 1) as i128) >> 32) as i64
+        }
+    }
+}
+
+#[cfg(feature = "float")]
+impl From<Fix32> for f32 {
+    fn from(val: Fix32) -> Self {
+        f64::from(val) as f32
+    }
+}
+
+#[cfg(feature = "float")]
+impl From<Fix32> for f64 {
+    fn from(val: Fix32) -> Self {
+        val.0 as f64 / FIX32_ONE.0 as f64
+    }
+}
+
+// Rounds away the low 16 fractional bits, failing if the integer part does
+// not fit in a Fix16.
+impl TryFrom<Fix32> for Fix16 {
+    type Error = TryFromFixedError;
+
+    fn try_from(val: Fix32) -> Result<Self, Self::Error> {
+        let res = if crate::no_rounding() {
+            val.0 >> 16
+        } else if val.0 >= 0 {
+            ((val.0 as i128 + 0x8000) >> 16) as i64
+        } else {
+            -((-(val.0 as i128) + 0x8000) >> 16) as i64
+        };
+
+        if res < i32::MIN as i64 || res > i32::MAX as i64 {
+            Err(TryFromFixedError(()))
+        } else {
+            Ok(Fix16(res as i32))
+        }
+    }
+}
+
+impl Fix32 {
+    pub fn abs(self) -> Fix32 {
+        if self.0.is_negative() {
+            Fix32(-self.0)
+        } else {
+            self
+        }
+    }
+
+    pub fn floor(self) -> Fix32 {
+        Fix32(self.0 & !0xFFFFFFFF)
+    }
+
+    pub fn ceil(self) -> Fix32 {
+        Fix32(
+            (self.0 & !0xFFFFFFFF)
+                + if (self.0 & 0xFFFFFFFF) != 0 {
+                    FIX32_ONE.0
+                } else {
+                    0
+                },
+        )
+    }
+
+    pub fn min(self, rhs: Fix32) -> Fix32 {
+        if self.0 <= rhs.0 {
+            self
+        } else {
+            rhs
+        }
+    }
+
+    pub fn max(self, rhs: Fix32) -> Fix32 {
+        if self.0 >= rhs.0 {
+            self
+        } else {
+            rhs
+        }
+    }
+
+    pub fn clamp(self, low: Fix32, high: Fix32) -> Fix32 {
+        self.min(low).max(high)
+    }
+
+    pub fn overflowing_add(self, rhs: Fix32) -> (Fix32, bool) {
+        let res = self.0.overflowing_add(rhs.0);
+        (Fix32(res.0), res.1)
+    }
+
+    pub fn overflowing_sub(self, rhs: Fix32) -> (Fix32, bool) {
+        let res = self.0.overflowing_sub(rhs.0);
+        (Fix32(res.0), res.1)
+    }
+
+    pub fn overflowing_mul(self, rhs: Fix32) -> (Fix32, bool) {
+        let mut product = self.0 as i128 * rhs.0 as i128;
+
+        // This adjustment is required in order to round -1/2 correctly.
+        if product < 0 && !crate::no_rounding() {
+            product -= 1;
+        }
+
+        // Rounding can carry past i64::MAX, so the overflow check is done on
+        // the rounded result.
+        let mut res = product >> 32;
+
+        if !crate::no_rounding() {
+            res += (product >> 31) & 1;
+        }
+
+        (Fix32(res as i64), res != res as i64 as i128)
+    }
+
+    pub fn overflowing_div(self, rhs: Fix32) -> (Fix32, bool) {
+        // Division by zero is reported as an overflow.
+        if rhs.0 == 0 {
+            return (FIX32_MIN, true);
+        }
+
+        // Compute one extra bit of the quotient to round with.
+        let mut quotient = ((self.0.unsigned_abs() as u128) << 33) / rhs.0.unsigned_abs() as u128;
+
+        if !crate::no_rounding() {
+            quotient += 1;
+        }
+
+        let res = quotient >> 1;
+
+        if (self.0 < 0) != (rhs.0 < 0) {
+            (Fix32((res as i64).wrapping_neg()), res > 1 << 63)
+        } else {
+            (Fix32(res as i64), res > i64::MAX as u128)
+        }
+    }
+
+    pub fn saturating_add(self, rhs: Fix32) -> Fix32 {
+        let res = self.0.overflowing_add(rhs.0);
+        if res.1 {
+            if self.0 >= 0 {
+                FIX32_MAX
+            } else {
+                FIX32_MIN
+            }
+        } else {
+            Fix32(res.0)
+        }
+    }
+
+    pub fn saturating_sub(self, rhs: Fix32) -> Fix32 {
+        let res = self.0.overflowing_sub(rhs.0);
+        if res.1 {
+            if self.0 >= 0 {
+                FIX32_MAX
+            } else {
+                FIX32_MIN
+            }
+        } else {
+            Fix32(res.0)
+        }
+    }
+
+    pub fn saturating_mul(self, rhs: Fix32) -> Fix32 {
+        let res = self.overflowing_mul(rhs);
+
+        if res.1 {
+            if self.0.is_positive() == rhs.0.is_positive() {
+                FIX32_MAX
+            } else {
+                FIX32_MIN
+            }
+        } else {
+            res.0
+        }
+    }
+
+    pub fn saturating_div(self, rhs: Fix32) -> Fix32 {
+        let res = self.overflowing_div(rhs);
+
+        if res.1 {
+            if (self.0 < 0) == (rhs.0 < 0) {
+                FIX32_MAX
+            } else {
+                FIX32_MIN
+            }
+        } else {
+            res.0
+        }
+    }
+
+    // The same as parsing with FromStr, but from raw bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Fix32, ParseFixedError> {
+        Fix32::from_magnitude(crate::parse::parse_fixed(bytes, 32)?)
+    }
+
+    // Hexadecimal with an optional sign and `0x` prefix, e.g. "-0x1.8".
+    pub fn from_hex_str(str: &str) -> Result<Fix32, ParseFixedError> {
+        Fix32::from_radix_str(str, 16)
+    }
+
+    // Parses the `int.frac` form printed by the Binary, Octal and hex
+    // formatting. Panics if `radix` is not 2, 8 or 16.
+    pub fn from_radix_str(str: &str, radix: u32) -> Result<Fix32, ParseFixedError> {
+        Fix32::from_magnitude(crate::parse::parse_radix_str(str.as_bytes(), radix, 32)?)
+    }
+
+    // Writes the Display form into `buf` without allocating.
+    pub fn format_into(self, buf: &mut [u8]) -> Result<&str, fmt::Error> {
+        crate::format::format_into(buf, format_args!("{}", self))
+    }
+
+    fn from_magnitude((negative, magnitude): (bool, u128)) -> Result<Fix32, ParseFixedError> {
+        if negative && magnitude <= 1 << 63 {
+            Ok(Fix32((magnitude as i64).wrapping_neg()))
+        } else if !negative && magnitude <= i64::MAX as u128 {
+            Ok(Fix32(magnitude as i64))
+        } else {
+            Err(ParseFixedError::Overflow)
+        }
+    }
+
+    pub fn sqrt(self) -> Fix32 {
+        let mut num = (self.0.unsigned_abs() as u128) << 32;
+        let mut res = 0u128;
+        let mut bit = 1u128 << 126;
+
+        while bit > num {
+            bit >>= 2;
+        }
+
+        while bit != 0 {
+            if num >= res + bit {
+                num -= res + bit;
+                res = (res >> 1) + bit;
+            } else {
+                res >>= 1;
+            }
+            bit >>= 2;
+        }
+
+        if !crate::no_rounding() && num > res {
+            res += 1;
+        }
+
+        if self.0.is_negative() {
+            Fix32(-(res as i64))
+        } else {
+            Fix32(res as i64)
+        }
+    }
+
+    pub fn sin(self) -> Fix32 {
+        let (angle, quadrant) = self.reduce_angle();
+
+        Fix32::from_q62(match quadrant {
+            0 => sin_q62(angle),
+            1 => cos_q62(angle),
+            2 => -sin_q62(angle),
+            _ => -cos_q62(angle),
+        })
+    }
+
+    pub fn cos(self) -> Fix32 {
+        let (angle, quadrant) = self.reduce_angle();
+
+        Fix32::from_q62(match quadrant {
+            0 => cos_q62(angle),
+            1 => -sin_q62(angle),
+            2 => -cos_q62(angle),
+            _ => sin_q62(angle),
+        })
+    }
+
+    pub fn tan(self) -> Fix32 {
+        let (angle, quadrant) = self.reduce_angle();
+        let (sin, cos) = match quadrant & 1 {
+            0 => (sin_q62(angle), cos_q62(angle)),
+            _ => (cos_q62(angle), -sin_q62(angle)),
+        };
+
+        if cos == 0 {
+            return FIX32_MAX;
+        }
+
+        let mut quotient = (sin.abs() << 33) / cos.abs();
+        if !crate::no_rounding() {
+            quotient += 1;
+        }
+
+        let res = (quotient >> 1).min(i64::MAX as i128) as i64;
+        if (sin < 0) != (cos < 0) {
+            Fix32(-res)
+        } else {
+            Fix32(res)
+        }
+    }
+
+    // Reduces the angle to [0, pi/2) in Q2.62, returning it with its quadrant.
+    fn reduce_angle(self) -> (i128, u32) {
+        let angle = (self.0 as i128) << 60;
+        let quadrant = angle.div_euclid(FRAC_PI_2_Q92) & 3;
+        let reduced = angle.rem_euclid(FRAC_PI_2_Q92) >> 30;
+
+        (reduced, quadrant as u32)
+    }
+
+    fn from_q62(val: i128) -> Fix32 {
+        if crate::no_rounding() {
+            Fix32((val >> 30) as i64)
+        } else {
+            Fix32(((val + (1 << 29)) >> 30) as i64)
+        }
+    }
+}
+
+// Taylor series for angles in [0, pi/2] in Q2.62, stopping once the terms no
+// longer affect the result.
+fn sin_q62(angle: i128) -> i128 {
+    let square = (angle * angle) >> 62;
+    let mut term = angle;
+    let mut sum = angle;
+    let mut n = 1;
+
+    while term != 0 {
+        term = -((term * square) >> 62) / ((n + 1) * (n + 2));
+        sum += term;
+        n += 2;
+    }
+
+    sum
+}
+
+fn cos_q62(angle: i128) -> i128 {
+    let square = (angle * angle) >> 62;
+    let mut term = 1 << 62;
+    let mut sum = term;
+    let mut n = 0;
+
+    while term != 0 {
+        term = -((term * square) >> 62) / ((n + 1) * (n + 2));
+        sum += term;
+        n += 2;
+    }
+
+    sum
+}
+
+impl ops::Add for Fix32 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        crate::operator_result(self.overflowing_add(rhs), FIX32_OVERFLOW, "add")
+    }
+}
+
+impl ops::AddAssign for Fix32 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl ops::Sub for Fix32 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        crate::operator_result(self.overflowing_sub(rhs), FIX32_OVERFLOW, "subtract")
+    }
+}
+
+impl ops::SubAssign for Fix32 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl ops::Mul for Fix32 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        crate::operator_result(self.overflowing_mul(rhs), FIX32_OVERFLOW, "multiply")
+    }
+}
+
+impl ops::MulAssign for Fix32 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl ops::Div for Fix32 {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        crate::check_divisor(rhs.0 == 0, "divide");
+        crate::operator_result(self.overflowing_div(rhs), FIX32_OVERFLOW, "divide")
+    }
+}
+
+impl ops::DivAssign for Fix32 {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl ops::Rem for Fix32 {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        crate::check_divisor(rhs.0 == 0, "calculate the remainder");
+        match self.0.checked_rem(rhs.0) {
+            Some(res) => Fix32(res),
+            None if rhs.0 == 0 => FIX32_OVERFLOW,
+            // MIN % -1 is zero, it only overflows the integer division.
+            None => Fix32(0),
+        }
+    }
+}
+
+impl ops::RemAssign for Fix32 {
+    fn rem_assign(&mut self, rhs: Self) {
+        *self = *self % rhs;
+    }
+}
+
+impl ops::Shl for Fix32 {
+    type Output = Self;
+
+    fn shl(self, rhs: Self) -> Self::Output {
+        Fix32(self.0 << i64::from(rhs))
+    }
+}
+
+impl ops::ShlAssign for Fix32 {
+    fn shl_assign(&mut self, rhs: Self) {
+        *self = *self << rhs;
+    }
+}
+
+impl ops::Shr for Fix32 {
+    type Output = Self;
+
+    fn shr(self, rhs: Self) -> Self::Output {
+        Fix32(self.0 >> i64::from(rhs))
+    }
+}
+
+impl ops::ShrAssign for Fix32 {
+    fn shr_assign(&mut self, rhs: Self) {
+        *self = *self >> rhs;
+    }
+}
+
+impl PartialEq for Fix32 {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
@@ -0,0 +1,1134 @@
+use core::{cmp::Ordering, convert::TryFrom, fmt, hash, iter, ops, str::FromStr};
+
+#[cfg(feature = "float")]
+use crate::rounding::round_f32;
+use crate::rounding::{mul_mode, round_div, round_sqrt, RoundingMode};
+
+// A signed fixed-point number with `FRAC` fractional bits, stored in an i32.
+// `FRAC` must be in 1..=30.
+//
+// Every primitive integer converts with a checked `TryFrom` except i32: the
+// infallible `From<i32>` is kept for compatibility, so `try_from(i32)` is core's
+// blanket impl and overflows like `from_int` instead of failing. Use
+// `checked_from_int` or `from_i32_saturating` for unchecked i32 input.
+#[derive(Copy, Clone)]
+pub struct Fixed<const FRAC: u32>(pub i32);
+
+impl<const FRAC: u32> Fixed<FRAC> {
+    const ONE_BITS: i32 = {
+        assert!(
+            FRAC >= 1 && FRAC <= 30,
+            "Fixed must have between 1 and 30 fractional bits"
+        );
+        1 << FRAC
+    };
+    const FRAC_MASK: i32 = Self::ONE_BITS - 1;
+
+    pub const MAX: Self = Fixed(i32::MAX);
+    pub const MIN: Self = Fixed(i32::MIN);
+    pub const ZERO: Self = Fixed(0);
+    pub const ONE: Self = Fixed(Self::ONE_BITS);
+    // The smallest positive value.
+    pub const EPSILON: Self = Fixed(1);
+
+    // Correctly rounded, which gives libfixmath's values for Q16.16. Neither
+    // fits in `Fixed<30>`, so using them there is a compile error.
+    pub const PI: Self = Self::from_bits_60(0x3243_F6A8_885A_308D);
+    pub const E: Self = Self::from_bits_60(0x2B7E_1516_28AE_D2A6);
+
+    // Rounds a positive constant with 60 fractional bits.
+    const fn from_bits_60(bits: u64) -> Self {
+        let res = (bits + (1 << (59 - FRAC))) >> (60 - FRAC);
+        assert!(
+            res <= i32::MAX as u64,
+            "constant out of range for this format"
+        );
+        Fixed(res as i32)
+    }
+}
+
+// The error returned when a checked conversion to or from a fixed-point type
+// does not fit in the target type.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TryFromFixedError(pub(crate) ());
+
+impl fmt::Display for TryFromFixedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("out of range fixed-point type conversion attempted")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TryFromFixedError {}
+
+// The error returned when parsing a fixed-point number from a string. `pos` is
+// the byte offset of the offending character.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParseFixedError {
+    // The input has no digits.
+    Empty,
+    InvalidDigit { pos: usize },
+    // The value does not fit in the target type.
+    Overflow,
+    // A binary, octal or hexadecimal fraction has more bits than the target
+    // type.
+    FractionTooLong,
+}
+
+impl fmt::Display for ParseFixedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseFixedError::Empty => {
+                f.write_str("cannot parse fixed-point number from empty string")
+            }
+            ParseFixedError::InvalidDigit { pos } => {
+                write!(f, "invalid digit found in string at position {}", pos)
+            }
+            ParseFixedError::Overflow => f.write_str("number too large to fit in target type"),
+            ParseFixedError::FractionTooLong => {
+                f.write_str("fractional part is more precise than the target type")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseFixedError {}
+
+crate::format::radix_fmt_impls!([const FRAC: u32] Fixed<FRAC>, |val: Fixed<FRAC>| {
+    let magnitude = val.0.unsigned_abs() as u64;
+    (val.0 < 0, magnitude >> FRAC, magnitude & Fixed::<FRAC>::FRAC_MASK as u64, FRAC)
+});
+
+impl<const FRAC: u32> FromStr for Fixed<FRAC> {
+    type Err = ParseFixedError;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        Self::from_bytes(str.as_bytes())
+    }
+}
+
+impl<'a, const FRAC: u32> TryFrom<&'a str> for Fixed<FRAC> {
+    type Error = ParseFixedError;
+
+    fn try_from(str: &'a str) -> Result<Self, Self::Error> {
+        str.parse()
+    }
+}
+
+// Q16.16 keeps the output it had before Fix16 became an alias, and other
+// formats include FRAC so that they can be told apart.
+impl<const FRAC: u32> fmt::Debug for Fixed<FRAC> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if FRAC == 16 {
+            f.debug_tuple("Fix16").field(&self.0).finish()
+        } else {
+            write!(f, "Fixed<{}>(", FRAC)?;
+            fmt::Debug::fmt(&self.0, f)?;
+            f.write_str(")")
+        }
+    }
+}
+
+impl<const FRAC: u32> fmt::Display for Fixed<FRAC> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let magnitude = self.0.unsigned_abs() as u64;
+        let int_part = magnitude >> FRAC;
+        let frac_bits = magnitude & Self::FRAC_MASK as u64;
+
+        crate::format::fmt_decimal(f, self.0 < 0, int_part, frac_bits, FRAC)
+    }
+}
+
+impl<const FRAC: u32> From<i32> for Fixed<FRAC> {
+    fn from(val: i32) -> Self {
+        Self::from_int(val)
+    }
+}
+
+macro_rules! int_conversions {
+    ($($t:ident $saturating:ident $try:tt),*) => {$(
+        int_conversions!(@try $try $t);
+
+        impl<const FRAC: u32> Fixed<FRAC> {
+            pub fn $saturating(val: $t) -> Self {
+                match Self::checked_from_i128(val as i128) {
+                    Some(res) => res,
+                    None if val > 0 => Fixed(i32::MAX),
+                    None => Fixed(i32::MIN),
+                }
+            }
+        }
+    )*};
+    (@try true $t:ident) => {
+        impl<const FRAC: u32> TryFrom<$t> for Fixed<FRAC> {
+            type Error = TryFromFixedError;
+
+            fn try_from(val: $t) -> Result<Self, Self::Error> {
+                Self::checked_from_i128(val as i128).ok_or(TryFromFixedError(()))
+            }
+        }
+    };
+    (@try false $t:ident) => {};
+}
+
+int_conversions!(
+    i8 from_i8_saturating true,
+    i16 from_i16_saturating true,
+    i32 from_i32_saturating false,
+    i64 from_i64_saturating true,
+    isize from_isize_saturating true,
+    u8 from_u8_saturating true,
+    u16 from_u16_saturating true,
+    u32 from_u32_saturating true,
+    u64 from_u64_saturating true,
+    usize from_usize_saturating true
+);
+
+// Rounds like `From<f32>`, but fails instead of saturating when out of range.
+#[cfg(feature = "float")]
+impl<const FRAC: u32> TryFrom<f64> for Fixed<FRAC> {
+    type Error = TryFromFixedError;
+
+    fn try_from(val: f64) -> Result<Self, Self::Error> {
+        let x = Self::scale_f64(val);
+
+        // Also false for NaN.
+        if x > i32::MIN as f64 - 1.0 && x < i32::MAX as f64 + 1.0 {
+            Ok(Fixed(x as i32))
+        } else {
+            Err(TryFromFixedError(()))
+        }
+    }
+}
+
+#[cfg(feature = "float")]
+impl<const FRAC: u32> From<f32> for Fixed<FRAC> {
+    fn from(val: f32) -> Self {
+        Fixed({
+            let mut x = val * Self::ONE_BITS as f32;
+
+            if !crate::no_rounding() {
+                x += if x.is_sign_positive() { 0.5 } else { -0.5 };
+            }
+
+            x as i32
+        })
+    }
+}
+
+impl<const FRAC: u32> From<Fixed<FRAC>> for i32 {
+    fn from(val: Fixed<FRAC>) -> Self {
+        let one = Fixed::<FRAC>::ONE_BITS;
+
+        if crate::no_rounding() {
+            val.0 >> FRAC
+        } else if val.0 >= 0 {
+            (val.0 + (one >> 1)) / one
+        } else {
+            (val.0 - (one >> 1)) / one
+        }
+    }
+}
+
+#[cfg(feature = "float")]
+impl<const FRAC: u32> From<Fixed<FRAC>> for f32 {
+    fn from(val: Fixed<FRAC>) -> Self {
+        val.0 as f32 / Fixed::<FRAC>::ONE_BITS as f32
+    }
+}
+
+// Always exact.
+#[cfg(feature = "float")]
+impl<const FRAC: u32> From<Fixed<FRAC>> for f64 {
+    fn from(val: Fixed<FRAC>) -> Self {
+        val.0 as f64 / Fixed::<FRAC>::ONE_BITS as f64
+    }
+}
+
+impl<const FRAC: u32> Fixed<FRAC> {
+    // Overflows like `val << FRAC` would, which is a compile error in a const.
+    pub const fn from_int(val: i32) -> Self {
+        Fixed(val * Self::ONE_BITS)
+    }
+
+    pub const fn checked_from_int(val: i32) -> Option<Self> {
+        match val.checked_mul(Self::ONE_BITS) {
+            Some(bits) => Some(Fixed(bits)),
+            None => None,
+        }
+    }
+
+    pub const fn from_bits(bits: i32) -> Self {
+        Fixed(bits)
+    }
+
+    pub const fn to_bits(self) -> i32 {
+        self.0
+    }
+
+    // The same as parsing with FromStr, but from raw bytes, e.g. straight out
+    // of a serial buffer.
+    pub const fn from_bytes(bytes: &[u8]) -> Result<Self, ParseFixedError> {
+        Self::from_parsed(crate::parse::parse_fixed(bytes, FRAC))
+    }
+
+    // Hexadecimal with an optional sign and `0x` prefix, e.g. "-0x1.8".
+    pub const fn from_hex_str(str: &str) -> Result<Self, ParseFixedError> {
+        Self::from_radix_str(str, 16)
+    }
+
+    // Parses the `int.frac` form printed by the Binary, Octal and hex
+    // formatting, with an optional sign and `0b`, `0o` or `0x` prefix. Panics
+    // if `radix` is not 2, 8 or 16.
+    pub const fn from_radix_str(str: &str, radix: u32) -> Result<Self, ParseFixedError> {
+        Self::from_parsed(crate::parse::parse_radix_str(str.as_bytes(), radix, FRAC))
+    }
+
+    // Writes the Display form into `buf` without allocating, failing if it
+    // does not fit. Any other format works through `core::fmt::Write`.
+    pub fn format_into(self, buf: &mut [u8]) -> Result<&str, fmt::Error> {
+        crate::format::format_into(buf, format_args!("{}", self))
+    }
+
+    const fn from_parsed(
+        parsed: Result<(bool, u128), ParseFixedError>,
+    ) -> Result<Self, ParseFixedError> {
+        let (negative, magnitude) = match parsed {
+            Ok(parsed) => parsed,
+            Err(err) => return Err(err),
+        };
+
+        if negative && magnitude <= 1 << 31 {
+            Ok(Fixed((magnitude as i32).wrapping_neg()))
+        } else if !negative && magnitude <= i32::MAX as u128 {
+            Ok(Fixed(magnitude as i32))
+        } else {
+            Err(ParseFixedError::Overflow)
+        }
+    }
+
+    pub const fn abs(self) -> Self {
+        if self.0.is_negative() {
+            Fixed(-self.0)
+        } else {
+            self
+        }
+    }
+
+    pub const fn floor(self) -> Self {
+        Fixed(self.0 & !Self::FRAC_MASK)
+    }
+
+    pub const fn ceil(self) -> Self {
+        Fixed(
+            (self.0 & !Self::FRAC_MASK)
+                + if (self.0 & Self::FRAC_MASK) != 0 {
+                    Self::ONE_BITS
+                } else {
+                    0
+                },
+        )
+    }
+
+    pub const fn min(self, rhs: Self) -> Self {
+        if self.0 <= rhs.0 {
+            self
+        } else {
+            rhs
+        }
+    }
+
+    pub const fn max(self, rhs: Self) -> Self {
+        if self.0 >= rhs.0 {
+            self
+        } else {
+            rhs
+        }
+    }
+
+    pub const fn clamp(self, low: Self, high: Self) -> Self {
+        self.min(low).max(high)
+    }
+
+    pub const fn overflowing_add(self, rhs: Self) -> (Self, bool) {
+        let res = self.0.overflowing_add(rhs.0);
+        (Fixed(res.0), res.1)
+    }
+
+    pub const fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
+        let res = self.0.overflowing_sub(rhs.0);
+        (Fixed(res.0), res.1)
+    }
+
+    pub const fn overflowing_mul(self, rhs: Self) -> (Self, bool) {
+        let mut product = self.0 as i64 * rhs.0 as i64;
+
+        // This adjustment is required in order to round -1/2 correctly.
+        if product < 0 && !crate::no_rounding() {
+            product -= 1;
+        }
+
+        // Rounding can carry past i32::MAX, so the overflow check is done on
+        // the rounded result.
+        let mut res = product >> FRAC;
+
+        if !crate::no_rounding() {
+            res += (product >> (FRAC - 1)) & 1;
+        }
+
+        (Fixed(res as i32), res != res as i32 as i64)
+    }
+
+    pub const fn overflowing_div(self, rhs: Self) -> (Self, bool) {
+        // Division by zero is reported as an overflow.
+        if rhs.0 == 0 {
+            return (Fixed(i32::MIN), true);
+        }
+
+        let res = div_bits(self.0.unsigned_abs(), rhs.0.unsigned_abs(), FRAC);
+
+        if ((self.0 as u32 ^ rhs.0 as u32) & 0x80000000) != 0 {
+            (Fixed((res as i32).wrapping_neg()), res > 1 << 31)
+        } else {
+            (Fixed(res as i32), res > i32::MAX as u64)
+        }
+    }
+
+    pub const fn saturating_add(self, rhs: Self) -> Self {
+        let res = self.0.overflowing_add(rhs.0);
+        if res.1 {
+            if self.0 >= 0 {
+                Fixed(i32::MAX)
+            } else {
+                Fixed(i32::MIN)
+            }
+        } else {
+            Fixed(res.0)
+        }
+    }
+
+    pub const fn saturating_sub(self, rhs: Self) -> Self {
+        let res = self.0.overflowing_sub(rhs.0);
+        if res.1 {
+            if self.0 >= 0 {
+                Fixed(i32::MAX)
+            } else {
+                Fixed(i32::MIN)
+            }
+        } else {
+            Fixed(res.0)
+        }
+    }
+
+    pub const fn saturating_mul(self, rhs: Self) -> Self {
+        let res = self.overflowing_mul(rhs);
+
+        if res.1 {
+            if self.0.is_positive() == rhs.0.is_positive() {
+                Fixed(i32::MAX)
+            } else {
+                Fixed(i32::MIN)
+            }
+        } else {
+            res.0
+        }
+    }
+
+    pub const fn saturating_div(self, rhs: Self) -> Self {
+        let res = self.overflowing_div(rhs);
+
+        if res.1 {
+            if (self.0 < 0) == (rhs.0 < 0) {
+                Fixed(i32::MAX)
+            } else {
+                Fixed(i32::MIN)
+            }
+        } else {
+            res.0
+        }
+    }
+
+    pub const fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.0.checked_add(rhs.0) {
+            Some(res) => Some(Fixed(res)),
+            None => None,
+        }
+    }
+
+    pub const fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.0.checked_sub(rhs.0) {
+            Some(res) => Some(Fixed(res)),
+            None => None,
+        }
+    }
+
+    pub const fn checked_mul(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_mul(rhs) {
+            (_, true) => None,
+            (res, false) => Some(res),
+        }
+    }
+
+    pub const fn checked_div(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_div(rhs) {
+            (_, true) => None,
+            (res, false) => Some(res),
+        }
+    }
+
+    // Only division by zero fails, since MIN % -1 is zero like with `%`.
+    pub const fn checked_rem(self, rhs: Self) -> Option<Self> {
+        match self.0.checked_rem(rhs.0) {
+            Some(res) => Some(Fixed(res)),
+            None if rhs.0 == 0 => None,
+            None => Some(Fixed(0)),
+        }
+    }
+
+    // self * a + b, rounded once from the exact result. The overflow flag and
+    // wrapped result are like overflowing_mul's.
+    pub fn overflowing_mul_add(self, a: Self, b: Self) -> (Self, bool) {
+        Self::overflowing_from_wide(self.0 as i128 * a.0 as i128 + ((b.0 as i128) << FRAC))
+    }
+
+    pub fn checked_mul_add(self, a: Self, b: Self) -> Option<Self> {
+        match self.overflowing_mul_add(a, b) {
+            (_, true) => None,
+            (res, false) => Some(res),
+        }
+    }
+
+    // Saturates instead of overflowing, only once at the end.
+    pub fn mul_add(self, a: Self, b: Self) -> Self {
+        Self::saturating_from_wide(self.0 as i128 * a.0 as i128 + ((b.0 as i128) << FRAC))
+    }
+
+    // The dot product of two equally long slices, summing the exact products
+    // and rounding once like mul_add.
+    pub fn overflowing_dot(a: &[Self], b: &[Self]) -> (Self, bool) {
+        Self::overflowing_from_wide(Self::dot_wide(a, b))
+    }
+
+    pub fn checked_dot(a: &[Self], b: &[Self]) -> Option<Self> {
+        match Self::overflowing_dot(a, b) {
+            (_, true) => None,
+            (res, false) => Some(res),
+        }
+    }
+
+    pub fn dot(a: &[Self], b: &[Self]) -> Self {
+        Self::saturating_from_wide(Self::dot_wide(a, b))
+    }
+
+    fn dot_wide(a: &[Self], b: &[Self]) -> i128 {
+        assert_eq!(a.len(), b.len(), "slices of different lengths");
+
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| a.0 as i64 * b.0 as i64)
+            .fold(0, |acc, product| acc + product as i128)
+    }
+
+    // Rounds a value with 2 * FRAC fractional bits like multiplication.
+    fn overflowing_from_wide(val: i128) -> (Self, bool) {
+        let res = round_div(val, Self::ONE_BITS as i128, mul_mode());
+        (Fixed(res as i32), res != res as i32 as i128)
+    }
+
+    fn saturating_from_wide(val: i128) -> Self {
+        let res = round_div(val, Self::ONE_BITS as i128, mul_mode());
+        Fixed(res.max(i32::MIN as i128).min(i32::MAX as i128) as i32)
+    }
+
+    // The operators as const fns, following the same overflow policy. Traits
+    // cannot be implemented as const, so the operators call these instead.
+    pub const fn const_add(self, rhs: Self) -> Self {
+        Self::const_result(self.overflowing_add(rhs), "attempt to add with overflow")
+    }
+
+    pub const fn const_sub(self, rhs: Self) -> Self {
+        Self::const_result(
+            self.overflowing_sub(rhs),
+            "attempt to subtract with overflow",
+        )
+    }
+
+    pub const fn const_mul(self, rhs: Self) -> Self {
+        Self::const_result(
+            self.overflowing_mul(rhs),
+            "attempt to multiply with overflow",
+        )
+    }
+
+    pub const fn const_div(self, rhs: Self) -> Self {
+        if rhs.0 == 0 && crate::overflow_checks() {
+            panic!("attempt to divide by zero");
+        }
+
+        Self::const_result(self.overflowing_div(rhs), "attempt to divide with overflow")
+    }
+
+    pub const fn const_neg(self) -> Self {
+        Self::const_result(
+            (Fixed(self.0.wrapping_neg()), self.0 == i32::MIN),
+            "attempt to negate with overflow",
+        )
+    }
+
+    pub const fn const_rem(self, rhs: Self) -> Self {
+        if rhs.0 == 0 && crate::overflow_checks() {
+            panic!("attempt to calculate the remainder by zero");
+        }
+
+        match self.0.checked_rem(rhs.0) {
+            Some(res) => Fixed(res),
+            None if rhs.0 == 0 => Fixed(i32::MIN),
+            // MIN % -1 is zero, it only overflows the integer division.
+            None => Fixed(0),
+        }
+    }
+
+    // crate::operator_result, which cannot be const because it formats the
+    // panic message.
+    const fn const_result((res, overflowed): (Self, bool), msg: &'static str) -> Self {
+        if !overflowed {
+            res
+        } else if crate::overflow_checks() {
+            panic!("{}", msg)
+        } else {
+            Fixed(i32::MIN)
+        }
+    }
+
+    // libfixmath's arithmetic regardless of the operator policy, so internal
+    // math never panics on an intermediate overflow or division by zero.
+    pub(crate) fn sentinel_add(self, rhs: Self) -> Self {
+        self.checked_add(rhs).unwrap_or(Fixed(i32::MIN))
+    }
+
+    pub(crate) fn sentinel_mul(self, rhs: Self) -> Self {
+        self.checked_mul(rhs).unwrap_or(Fixed(i32::MIN))
+    }
+
+    pub(crate) fn sentinel_div(self, rhs: Self) -> Self {
+        self.checked_div(rhs).unwrap_or(Fixed(i32::MIN))
+    }
+
+    pub const fn checked_neg(self) -> Option<Self> {
+        match self.0.checked_neg() {
+            Some(res) => Some(Fixed(res)),
+            None => None,
+        }
+    }
+
+    pub const fn checked_abs(self) -> Option<Self> {
+        match self.0.checked_abs() {
+            Some(res) => Some(Fixed(res)),
+            None => None,
+        }
+    }
+
+    // Unlike sqrt, negative inputs are a domain error rather than mirrored.
+    pub const fn checked_sqrt(self) -> Option<Self> {
+        if self.0 < 0 {
+            None
+        } else {
+            Some(self.sqrt())
+        }
+    }
+
+    pub const fn sqrt(self) -> Self {
+        // Q16.16 follows libfixmath bit for bit, which rounds some large
+        // inputs differently from the exact square root.
+        let res = if FRAC == 16 {
+            sqrt_q16(self.0.unsigned_abs())
+        } else {
+            sqrt_exact((self.0.unsigned_abs() as u64) << FRAC)
+        };
+
+        if self.0.is_negative() {
+            Fixed(-(res as i32))
+        } else {
+            Fixed(res as i32)
+        }
+    }
+
+    // Variants of the arithmetic and conversions that round with an explicit
+    // mode instead of following the `no-rounding` feature.
+    pub fn overflowing_mul_round(self, rhs: Self, mode: RoundingMode) -> (Self, bool) {
+        let product = self.0 as i128 * rhs.0 as i128;
+        let res = round_div(product, Self::ONE_BITS as i128, mode);
+
+        (Fixed(res as i32), res != res as i32 as i128)
+    }
+
+    pub fn mul_round(self, rhs: Self, mode: RoundingMode) -> Self {
+        crate::operator_result(
+            self.overflowing_mul_round(rhs, mode),
+            Fixed(i32::MIN),
+            "multiply",
+        )
+    }
+
+    pub fn overflowing_div_round(self, rhs: Self, mode: RoundingMode) -> (Self, bool) {
+        if rhs.0 == 0 {
+            return (Fixed(i32::MIN), true);
+        }
+
+        let num = (self.0 as i128) << FRAC;
+        let res = if rhs.0 < 0 {
+            round_div(-num, -(rhs.0 as i128), mode)
+        } else {
+            round_div(num, rhs.0 as i128, mode)
+        };
+
+        (Fixed(res as i32), res != res as i32 as i128)
+    }
+
+    pub fn div_round(self, rhs: Self, mode: RoundingMode) -> Self {
+        crate::check_divisor(rhs.0 == 0, "divide");
+        crate::operator_result(
+            self.overflowing_div_round(rhs, mode),
+            Fixed(i32::MIN),
+            "divide",
+        )
+    }
+
+    // Negative inputs are mirrored like sqrt, and the result is always the
+    // correctly rounded square root, including for Q16.16.
+    pub fn sqrt_round(self, mode: RoundingMode) -> Self {
+        let (floor, rem) = isqrt_rem((self.0.unsigned_abs() as u64) << FRAC);
+        let res = round_sqrt(floor, rem, self.0 < 0, mode) as i32;
+
+        if self.0 < 0 {
+            Fixed(-res)
+        } else {
+            Fixed(res)
+        }
+    }
+
+    // Saturates like the `From<f32>` conversion.
+    #[cfg(feature = "float")]
+    pub fn from_f32_round(val: f32, mode: RoundingMode) -> Self {
+        let res = round_f32(val * Self::ONE_BITS as f32, mode);
+        Fixed(res.max(i32::MIN as i64).min(i32::MAX as i64) as i32)
+    }
+
+    pub fn to_i32_round(self, mode: RoundingMode) -> i32 {
+        round_div(self.0 as i128, Self::ONE_BITS as i128, mode) as i32
+    }
+
+    // Converts to any primitive integer, failing if the rounded value does not
+    // fit, e.g. `val.to_int_round::<u8>(RoundingMode::Floor)`.
+    pub fn to_int_round<T: TryFrom<i32>>(self, mode: RoundingMode) -> Result<T, TryFromFixedError> {
+        T::try_from(self.to_i32_round(mode)).map_err(|_| TryFromFixedError(()))
+    }
+
+    // Saturates and maps NaN to zero like `From<f32>`.
+    #[cfg(feature = "float")]
+    pub fn from_f64_saturating(val: f64) -> Self {
+        Fixed(Self::scale_f64(val) as i32)
+    }
+
+    #[cfg(feature = "float")]
+    fn scale_f64(val: f64) -> f64 {
+        let mut x = val * Self::ONE_BITS as f64;
+
+        if !crate::no_rounding() {
+            x += if x.is_sign_positive() { 0.5 } else { -0.5 };
+        }
+
+        x
+    }
+
+    fn checked_from_i128(val: i128) -> Option<Self> {
+        // Every primitive up to 64 bits still fits in an i128 after the shift.
+        let bits = val << FRAC;
+
+        if bits == bits as i32 as i128 {
+            Some(Fixed(bits as i32))
+        } else {
+            None
+        }
+    }
+
+    // Converts to a format with `TO` fractional bits, rounding away any bits
+    // that do not fit. The flag is set if the integer part does not fit.
+    pub fn overflowing_rescale<const TO: u32>(self) -> (Fixed<TO>, bool) {
+        let val = self.0 as i64;
+
+        let res = if TO >= FRAC {
+            val << (TO - FRAC)
+        } else {
+            let shift = FRAC - TO;
+
+            if crate::no_rounding() {
+                val >> shift
+            } else if val >= 0 {
+                (val + (1 << (shift - 1))) >> shift
+            } else {
+                -((-val + (1 << (shift - 1))) >> shift)
+            }
+        };
+
+        (Fixed(res as i32), res != res as i32 as i64)
+    }
+
+    pub fn rescale<const TO: u32>(self) -> Fixed<TO> {
+        match self.overflowing_rescale() {
+            (_, true) => Fixed(i32::MIN),
+            (res, false) => res,
+        }
+    }
+
+    pub fn saturating_rescale<const TO: u32>(self) -> Fixed<TO> {
+        match self.overflowing_rescale() {
+            (_, true) if self.0 >= 0 => Fixed(i32::MAX),
+            (_, true) => Fixed(i32::MIN),
+            (res, false) => res,
+        }
+    }
+
+    // Converts to a format with `TO` fractional bits only if no integer or
+    // fractional bits are lost.
+    pub fn lossless_rescale<const TO: u32>(self) -> Option<Fixed<TO>> {
+        let res = (self.0 as i64) << TO >> FRAC;
+
+        if res != res as i32 as i64 || (res << FRAC >> TO) != self.0 as i64 {
+            None
+        } else {
+            Some(Fixed(res as i32))
+        }
+    }
+}
+
+// This uses a hardware 32/32 bit division multiple times, until we have
+// computed all the bits in (a << (frac + 1)) / b. Usually this takes 1-3
+// iterations. Returns the rounded magnitude of the quotient.
+const fn div_bits(mut remainder: u32, mut divider: u32, frac: u32) -> u64 {
+    // The kick-start below is tuned for 17 quotient bits and leaves the long
+    // division too few steps for finer formats, so only Q16.16 keeps the
+    // libfixmath algorithm. The shifted remainder always fits in a u64.
+    if frac != 16 {
+        let quotient = ((remainder as u64) << (frac + 1)) / divider as u64;
+
+        return if crate::no_rounding() {
+            quotient >> 1
+        } else {
+            (quotient + 1) >> 1
+        };
+    }
+
+    let mut quotient: u64 = 0;
+    let mut bit_pos = frac as i32 + 1;
+
+    // Kick-start the division a bit.
+    // This improves speed in the worst-case scenarios where N and D are large
+    // It gets a lower estimate for the result by N/(D >> (frac + 1) + 1).
+    if (divider & 0xFFF0_0000) != 0 {
+        let shifted_div = (divider >> (frac + 1)) + 1;
+        quotient = (remainder / shifted_div) as u64;
+        remainder -= ((quotient * divider as u64) >> (frac + 1)) as u32;
+    }
+
+    // If the divider is divisible by 2^n, take advantage of it.
+    while (divider & 0xF) == 0 && bit_pos >= 4 {
+        divider >>= 4;
+        bit_pos -= 4;
+    }
+
+    while remainder != 0 && bit_pos >= 0 {
+        let mut shift = remainder.leading_zeros();
+        if shift > bit_pos as u32 {
+            shift = bit_pos as u32;
+        }
+
+        remainder <<= shift;
+        bit_pos -= shift as i32;
+
+        let div = remainder / divider;
+        remainder %= divider;
+        quotient += (div as u64) << bit_pos;
+
+        remainder <<= 1;
+        bit_pos -= 1;
+    }
+
+    if !crate::no_rounding() {
+        quotient += 1;
+    }
+
+    quotient >> 1
+}
+
+pub(crate) const fn sqrt_q16(mut num: u32) -> u32 {
+    let mut res = 0u32;
+    let mut bit;
+
+    if (num & 0xFFF00000) != 0 {
+        bit = 1u32 << 30;
+    } else {
+        bit = 1u32 << 18;
+    }
+
+    while bit > num {
+        bit >>= 2;
+    }
+
+    let mut x = 0;
+    while x < 2 {
+        while bit != 0 {
+            if num >= res + bit {
+                num -= res + bit;
+                res = (res >> 1) + bit;
+            } else {
+                res >>= 1;
+            }
+            bit >>= 2;
+        }
+
+        if x == 0 {
+            if num > u16::MAX as u32 {
+                num -= res;
+                num = (num << 16) - 0x8000;
+                res = (res << 16) + 0x8000;
+            } else {
+                num <<= 16;
+                res <<= 16;
+            }
+
+            bit = 1 << 14;
+        }
+
+        x += 1;
+    }
+
+    if !crate::no_rounding() && num > res {
+        res += 1;
+    }
+
+    res
+}
+
+const fn sqrt_exact(num: u64) -> u32 {
+    let (res, rem) = isqrt_rem(num);
+
+    if !crate::no_rounding() && rem > res {
+        (res + 1) as u32
+    } else {
+        res as u32
+    }
+}
+
+// Returns the floor of the square root and the remainder `num - res * res`.
+pub(crate) const fn isqrt_rem(mut num: u64) -> (u64, u64) {
+    let mut res = 0u64;
+    let mut bit = 1u64 << 62;
+
+    while bit > num {
+        bit >>= 2;
+    }
+
+    while bit != 0 {
+        if num >= res + bit {
+            num -= res + bit;
+            res = (res >> 1) + bit;
+        } else {
+            res >>= 1;
+        }
+        bit >>= 2;
+    }
+
+    (res, num)
+}
+
+impl<const FRAC: u32> ops::Add for Fixed<FRAC> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.const_add(rhs)
+    }
+}
+
+impl<const FRAC: u32> ops::AddAssign for Fixed<FRAC> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const FRAC: u32> ops::Sub for Fixed<FRAC> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.const_sub(rhs)
+    }
+}
+
+impl<const FRAC: u32> ops::SubAssign for Fixed<FRAC> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const FRAC: u32> ops::Mul for Fixed<FRAC> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.const_mul(rhs)
+    }
+}
+
+impl<const FRAC: u32> ops::MulAssign for Fixed<FRAC> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<const FRAC: u32> ops::Div for Fixed<FRAC> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.const_div(rhs)
+    }
+}
+
+impl<const FRAC: u32> ops::DivAssign for Fixed<FRAC> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<const FRAC: u32> ops::Rem for Fixed<FRAC> {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.const_rem(rhs)
+    }
+}
+
+impl<const FRAC: u32> ops::RemAssign for Fixed<FRAC> {
+    fn rem_assign(&mut self, rhs: Self) {
+        *self = *self % rhs;
+    }
+}
+
+impl<const FRAC: u32> ops::Shl for Fixed<FRAC> {
+    type Output = Self;
+
+    fn shl(self, rhs: Self) -> Self::Output {
+        Fixed(self.0 << i32::from(rhs))
+    }
+}
+
+impl<const FRAC: u32> ops::ShlAssign for Fixed<FRAC> {
+    fn shl_assign(&mut self, rhs: Self) {
+        *self = *self << rhs;
+    }
+}
+
+impl<const FRAC: u32> ops::Shr for Fixed<FRAC> {
+    type Output = Self;
+
+    fn shr(self, rhs: Self) -> Self::Output {
+        Fixed(self.0 >> i32::from(rhs))
+    }
+}
+
+impl<const FRAC: u32> ops::ShrAssign for Fixed<FRAC> {
+    fn shr_assign(&mut self, rhs: Self) {
+        *self = *self >> rhs;
+    }
+}
+
+impl<const FRAC: u32> PartialEq for Fixed<FRAC> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<const FRAC: u32> Eq for Fixed<FRAC> {}
+
+impl<const FRAC: u32> PartialOrd for Fixed<FRAC> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const FRAC: u32> Ord for Fixed<FRAC> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl<const FRAC: u32> hash::Hash for Fixed<FRAC> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl<const FRAC: u32> Default for Fixed<FRAC> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<const FRAC: u32> ops::Neg for Fixed<FRAC> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self.const_neg()
+    }
+}
+
+// The bitwise operators work on the raw bits. There is deliberately no Not,
+// as `!x` is `-x - EPSILON` and easily mistaken for a negation.
+macro_rules! bit_ops {
+    ($($trait:ident $fn:ident $assign_trait:ident $assign_fn:ident $op:tt),*) => {$(
+        impl<const FRAC: u32> ops::$trait for Fixed<FRAC> {
+            type Output = Self;
+
+            fn $fn(self, rhs: Self) -> Self::Output {
+                Fixed(self.0 $op rhs.0)
+            }
+        }
+
+        impl<const FRAC: u32> ops::$assign_trait for Fixed<FRAC> {
+            fn $assign_fn(&mut self, rhs: Self) {
+                *self = *self $op rhs;
+            }
+        }
+    )*};
+}
+
+bit_ops!(
+    BitAnd bitand BitAndAssign bitand_assign &,
+    BitOr bitor BitOrAssign bitor_assign |,
+    BitXor bitxor BitXorAssign bitxor_assign ^
+);
+
+// These follow the operator overflow policy. Wrap the values in Saturating or
+// Wrapping for the other behaviours.
+impl<const FRAC: u32> iter::Sum for Fixed<FRAC> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, val| acc + val)
+    }
+}
+
+impl<'a, const FRAC: u32> iter::Sum<&'a Self> for Fixed<FRAC> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl<const FRAC: u32> iter::Product for Fixed<FRAC> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, val| acc * val)
+    }
+}
+
+impl<'a, const FRAC: u32> iter::Product<&'a Self> for Fixed<FRAC> {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().product()
+    }
+}
@@ -0,0 +1,248 @@
+use core::fmt::{self, Write};
+
+use crate::parse::frac_ulps;
+
+// Long enough for any body written here: 64 binary integer digits, the point
+// and 32 fractional digits.
+const BODY_LEN: usize = 128;
+
+// A fmt::Write sink over a caller-provided buffer, so formatting never needs
+// the heap. Writes fail once the buffer is full.
+pub(crate) struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> SliceWriter<'a> {
+    pub(crate) fn new(buf: &'a mut [u8]) -> Self {
+        SliceWriter { buf, len: 0 }
+    }
+
+    pub(crate) fn into_str(self) -> &'a str {
+        // Only whole `str`s are ever copied in, so this is always valid UTF-8.
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or("")
+    }
+}
+
+impl Write for SliceWriter<'_> {
+    fn write_str(&mut self, str: &str) -> fmt::Result {
+        let end = self.len + str.len();
+        if end > self.buf.len() {
+            return Err(fmt::Error);
+        }
+
+        self.buf[self.len..end].copy_from_slice(str.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+pub(crate) fn format_into<'a>(
+    buf: &'a mut [u8],
+    args: fmt::Arguments<'_>,
+) -> Result<&'a str, fmt::Error> {
+    let mut writer = SliceWriter::new(buf);
+    writer.write_fmt(args)?;
+    Ok(writer.into_str())
+}
+
+// Formatter::pad_integral, plus `zeros` trailing zeros that are written
+// directly so that large precisions need no buffer.
+fn pad_number(
+    f: &mut fmt::Formatter<'_>,
+    negative: bool,
+    prefix: &str,
+    body: &str,
+    zeros: usize,
+) -> fmt::Result {
+    let sign = if negative {
+        "-"
+    } else if f.sign_plus() {
+        "+"
+    } else {
+        ""
+    };
+    let prefix = if f.alternate() { prefix } else { "" };
+
+    let len = sign.len() + prefix.len() + body.len() + zeros;
+    let padding = f.width().unwrap_or(0).saturating_sub(len);
+    let (pre, zero_pad, post) = match f.align() {
+        _ if f.sign_aware_zero_pad() => (0, padding, 0),
+        Some(fmt::Alignment::Left) => (0, 0, padding),
+        Some(fmt::Alignment::Center) => (padding / 2, 0, padding.div_ceil(2)),
+        _ => (padding, 0, 0),
+    };
+
+    let fill = f.fill();
+    for _ in 0..pre {
+        f.write_char(fill)?;
+    }
+    f.write_str(sign)?;
+    f.write_str(prefix)?;
+    for _ in 0..zero_pad {
+        f.write_char('0')?;
+    }
+    f.write_str(body)?;
+    for _ in 0..zeros {
+        f.write_char('0')?;
+    }
+    for _ in 0..post {
+        f.write_char(fill)?;
+    }
+
+    Ok(())
+}
+
+// Writes `int_part + frac_bits / 2^frac` in decimal using only integer math.
+//
+// Without a precision this is the shortest decimal that parses back to the
+// same bits, `{:.N}` rounds to N places with ties away from zero, and `{:#}`
+// prints the exact value with all `frac` decimal places.
+pub(crate) fn fmt_decimal(
+    f: &mut fmt::Formatter<'_>,
+    negative: bool,
+    mut int_part: u64,
+    frac_bits: u64,
+    frac: u32,
+) -> fmt::Result {
+    // Every binary fraction is an exact decimal with `frac` places.
+    let exact = frac_bits as u128 * 5u128.pow(frac);
+
+    let (places, mut digits) = match f.precision() {
+        Some(places) if places >= frac as usize => (places, exact),
+        Some(places) => (places, round_places(exact, frac, places as u32)),
+        None if f.alternate() => (frac as usize, exact),
+        None => shortest(exact, frac_bits, frac),
+    };
+
+    if places < frac as usize && digits == 10u128.pow(places as u32) {
+        int_part += 1;
+        digits = 0;
+    }
+
+    let mut buf = [0; BODY_LEN];
+    let mut body = SliceWriter::new(&mut buf);
+    let shown = places.min(frac as usize);
+
+    write!(body, "{}", int_part)?;
+    if places > 0 {
+        write!(body, ".{:0width$}", digits, width = shown)?;
+    }
+
+    pad_number(f, negative, "", body.into_str(), places - shown)
+}
+
+// Rounds `exact`, which has `frac` decimal places, to `places` decimal places.
+fn round_places(exact: u128, frac: u32, places: u32) -> u128 {
+    let unit = 10u128.pow(frac - places);
+
+    if exact % unit * 2 >= unit {
+        exact / unit + 1
+    } else {
+        exact / unit
+    }
+}
+
+fn shortest(exact: u128, frac_bits: u64, frac: u32) -> (usize, u128) {
+    for places in 0..frac {
+        let unit = 10u128.pow(frac - places);
+        let (low, rem) = (exact / unit, exact % unit);
+
+        // Prefer whichever neighbour is closer to the exact value.
+        let candidates = if rem * 2 >= unit {
+            [low + 1, low]
+        } else {
+            [low, low + 1]
+        };
+
+        for &digits in &candidates {
+            let parsed = frac_ulps(digits * 10u128.pow(frac + 1 - places), frac);
+            if parsed == frac_bits as u128 {
+                return (places as usize, digits);
+            }
+        }
+    }
+
+    (frac as usize, exact)
+}
+
+// Writes the value in base 2^bits as `int.frac` with trailing zeros trimmed,
+// e.g. `32.69` in hexadecimal. The fraction is always exact, and negative values
+// are printed as a sign and magnitude so that they parse back.
+pub(crate) fn fmt_radix(
+    f: &mut fmt::Formatter<'_>,
+    negative: bool,
+    int_part: u64,
+    frac_bits: u64,
+    frac: u32,
+    bits: u32,
+    upper: bool,
+) -> fmt::Result {
+    let prefix = match bits {
+        1 => "0b",
+        3 => "0o",
+        _ => "0x",
+    };
+    let digit = |d: u128| {
+        let c = core::char::from_digit(d as u32, 1 << bits).unwrap_or('?');
+        if upper {
+            c.to_ascii_uppercase()
+        } else {
+            c
+        }
+    };
+
+    let mut buf = [0; BODY_LEN];
+    let mut body = SliceWriter::new(&mut buf);
+    let mut empty = true;
+    let int_part = int_part as u128;
+    let mut shift = 64u32.div_ceil(bits) * bits;
+    while shift > 0 && int_part >> (shift - bits) == 0 {
+        shift -= bits;
+    }
+    while shift > 0 {
+        shift -= bits;
+        body.write_char(digit((int_part >> shift) & ((1 << bits) - 1)))?;
+        empty = false;
+    }
+    if empty {
+        body.write_char('0')?;
+    }
+
+    if frac_bits != 0 {
+        // Pad the fraction on the right to a whole number of digits.
+        let width = frac.div_ceil(bits) * bits;
+        let mut frac_bits = (frac_bits as u128) << (width - frac);
+        let mut remaining = width;
+
+        body.write_char('.')?;
+        while frac_bits != 0 {
+            remaining -= bits;
+            body.write_char(digit(frac_bits >> remaining))?;
+            frac_bits &= (1 << remaining) - 1;
+        }
+    }
+
+    pad_number(f, negative, prefix, body.into_str(), 0)
+}
+
+// Implements Binary, Octal, LowerHex and UpperHex with fmt_radix, given a
+// function splitting a value into (negative, int_part, frac_bits, frac).
+macro_rules! radix_fmt_impls {
+    ([$($gen:tt)*] $t:ty, $parts:expr) => {
+        crate::format::radix_fmt_impls!(@impl [$($gen)*] $t, $parts, Binary, 1, false);
+        crate::format::radix_fmt_impls!(@impl [$($gen)*] $t, $parts, Octal, 3, false);
+        crate::format::radix_fmt_impls!(@impl [$($gen)*] $t, $parts, LowerHex, 4, false);
+        crate::format::radix_fmt_impls!(@impl [$($gen)*] $t, $parts, UpperHex, 4, true);
+    };
+    (@impl [$($gen:tt)*] $t:ty, $parts:expr, $trait:ident, $bits:literal, $upper:literal) => {
+        impl<$($gen)*> core::fmt::$trait for $t {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                let (negative, int_part, frac_bits, frac) = ($parts)(*self);
+                crate::format::fmt_radix(f, negative, int_part, frac_bits, frac, $bits, $upper)
+            }
+        }
+    };
+}
+
+pub(crate) use radix_fmt_impls;
@@ -0,0 +1,104 @@
+use core::ops;
+
+use crate::fix16::Fix16;
+
+// An unsigned 0.32 fraction in [0, 1), e.g. for probabilities and blend
+// factors.
+#[derive(Copy, Clone, Debug)]
+pub struct Fract32(pub u32);
+
+pub const FRACT32_MAX: Fract32 = Fract32(0xFFFFFFFF);
+pub const FRACT32_MIN: Fract32 = Fract32(0);
+
+impl From<Fract32> for Fix16 {
+    fn from(val: Fract32) -> Self {
+        if crate::no_rounding() {
+            Fix16((val.0 >> 16) as i32)
+        } else {
+            Fix16(((val.0 as u64 + 0x8000) >> 16) as i32)
+        }
+    }
+}
+
+#[cfg(feature = "float")]
+impl From<Fract32> for f32 {
+    fn from(val: Fract32) -> Self {
+        (val.0 as f64 / 4294967296.0) as f32
+    }
+}
+
+impl Fract32 {
+    // Saturates to FRACT32_MAX when the fraction is not below one, which
+    // also covers a zero denominator.
+    pub fn new(numerator: u32, denominator: u32) -> Fract32 {
+        if denominator <= numerator {
+            return FRACT32_MAX;
+        }
+
+        Fract32((((numerator as u64) << 32) / denominator as u64) as u32)
+    }
+
+    pub fn invert(self) -> Fract32 {
+        Fract32(0xFFFFFFFF - self.0)
+    }
+
+    pub fn mul_u32(self, val: u32) -> u32 {
+        ((val as u64 * self.0 as u64) >> 32) as u32
+    }
+
+    pub fn mul_i32(self, val: i32) -> i32 {
+        if val < 0 {
+            (self.mul_u32(val.unsigned_abs()) as i32).wrapping_neg()
+        } else {
+            self.mul_u32(val as u32) as i32
+        }
+    }
+
+    pub fn mul_fix16(self, val: Fix16) -> Fix16 {
+        Fix16(self.mul_i32(val.0))
+    }
+}
+
+impl ops::Mul<u32> for Fract32 {
+    type Output = u32;
+
+    fn mul(self, rhs: u32) -> Self::Output {
+        self.mul_u32(rhs)
+    }
+}
+
+impl ops::Mul<i32> for Fract32 {
+    type Output = i32;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        self.mul_i32(rhs)
+    }
+}
+
+impl ops::Mul<Fix16> for Fract32 {
+    type Output = Fix16;
+
+    fn mul(self, rhs: Fix16) -> Self::Output {
+        self.mul_fix16(rhs)
+    }
+}
+
+impl ops::Mul<Fract32> for Fix16 {
+    type Output = Fix16;
+
+    fn mul(self, rhs: Fract32) -> Self::Output {
+        rhs.mul_fix16(self)
+    }
+}
+
+impl ops::MulAssign<Fract32> for Fix16 {
+    fn mul_assign(&mut self, rhs: Fract32) {
+        *self = *self * rhs;
+    }
+}
+
+impl PartialEq for Fract32 {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
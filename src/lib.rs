@@ -1,17 +1,85 @@
+//! A reimplementation of libfixmath in Rust.
+//!
+//! `Fix16` is an alias of `Fixed<16>`, so patterns such as `let Fix16(bits) = x;`
+//! no longer compile. Use `x.0`, `x.to_bits()` or `Fixed(bits)` instead. See
+//! CHANGELOG.md for the other breaking changes.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+// Without the `float` feature nothing may touch floating point, so that no
+// soft-float routines are linked on targets without an FPU. CI also scans the
+// float-free build itself, since the lint misses casts.
+#![cfg_attr(not(feature = "float"), deny(clippy::float_arithmetic))]
+
 #[allow(dead_code)]
 
-pub fn no_rounding() -> bool {
-    #[cfg(feature = "no-rounding")]
-    return true;
-    false
+pub const fn no_rounding() -> bool {
+    cfg!(feature = "no-rounding")
+}
+
+// Operators panic on overflow and division by zero like the primitive integers
+// when this is set, and otherwise return the libfixmath overflow sentinel.
+pub const fn overflow_checks() -> bool {
+    cfg!(any(debug_assertions, feature = "overflow-checks"))
+}
+
+pub(crate) fn operator_result<T>(res: (T, bool), sentinel: T, op: &str) -> T {
+    match res {
+        (res, false) => res,
+        (_, true) if overflow_checks() => panic!("attempt to {} with overflow", op),
+        (_, true) => sentinel,
+    }
+}
+
+pub(crate) fn check_divisor(zero: bool, op: &str) {
+    if zero && overflow_checks() {
+        panic!("attempt to {} by zero", op);
+    }
 }
 
 mod consts;
 pub mod fix16;
+pub mod fix32;
+pub mod fixed;
+mod format;
+pub mod fract32;
+mod parse;
+pub mod rounding;
+pub mod saturating;
+pub mod ufix16;
+pub mod wrapping;
+
+// The main tests build their inputs from floats, so without the `float` feature
+// only the integer paths are exercised.
+#[cfg(all(test, not(feature = "float")))]
+mod float_free_tests {
+    use crate::fix16::Fix16;
+
+    #[test]
+    fn integer_only() {
+        let x: Fix16 = "2.5".parse().unwrap();
 
-#[cfg(test)]
+        assert_eq!((x * x).to_string(), "6.25");
+        if !crate::no_rounding() {
+            assert_eq!(Fix16::from(2).sqrt().to_string(), "1.41422");
+            assert_eq!(format!("{:.4}", (Fix16::PI / Fix16::from(6)).sin()), "0.5000");
+        }
+        assert_eq!(i32::from("-7.5".parse::<Fix16>().unwrap()), -8);
+    }
+}
+
+#[cfg(all(test, feature = "float"))]
 mod tests {
-    use crate::fix16::{FIX16_MAX, FIX16_MIN, FIX16_ONE, Fix16};
+    use crate::fix16;
+    use crate::fix16::{FIX16_OVERFLOW, Fix16};
+    use crate::fix32::{FIX32_MAX, FIX32_MIN, FIX32_ONE, FIX32_OVERFLOW, FIX32_PI, Fix32};
+    use crate::fixed::{Fixed, ParseFixedError, TryFromFixedError};
+    use crate::fract32::{FRACT32_MAX, Fract32};
+    use crate::rounding::RoundingMode;
+    use crate::saturating::Saturating;
+    use crate::ufix16::{UFIX16_MAX, UFIX16_ONE, UFIX16_OVERFLOW, UFix16};
+    use crate::wrapping::Wrapping;
+    use std::convert::TryFrom;
+    use std::str::FromStr;
 
     extern crate libfixmath_src_rs;
     use libfixmath_src_rs::*;
@@ -40,7 +108,10 @@ mod tests {
         }
         assert_eq!(rusty_sub.0, native_sub);
 
-        let rusty_mul = rusty_two_thousand_thirty_nine_dot_four_two * rusty_twenty;
+        // 40788.4 is out of range, where libfixmath returns its overflow sentinel.
+        let rusty_mul = rusty_two_thousand_thirty_nine_dot_four_two
+            .checked_mul(rusty_twenty)
+            .unwrap_or(FIX16_OVERFLOW);
         let native_mul;
         unsafe {
             native_mul = fix16_mul(native_two_thousand_thirty_nine_dot_four_two, native_twenty);
@@ -74,6 +145,89 @@ mod tests {
             native_tan = fix16_tan(native_two_thousand_thirty_nine_dot_four_two);
         }
         assert_eq!(rusty_tan.0, native_tan);
+
+        let rusty_atan = rusty_two_thousand_thirty_nine_dot_four_two.atan();
+        let native_atan;
+        unsafe {
+            native_atan = fix16_atan(native_two_thousand_thirty_nine_dot_four_two);
+        }
+        assert_eq!(rusty_atan.0, native_atan);
+
+        let rusty_atan2 = rusty_twenty.atan2(Fix16(-rusty_two_thousand_thirty_nine_dot_four_two.0));
+        let native_atan2;
+        unsafe {
+            native_atan2 = fix16_atan2(native_twenty, -native_two_thousand_thirty_nine_dot_four_two);
+        }
+        assert_eq!(rusty_atan2.0, native_atan2);
+
+        for raw in (-Fix16::ONE.0 - 0x100..=Fix16::ONE.0 + 0x100).step_by(0x7F) {
+            let rusty_asin = Fix16(raw).asin();
+            let native_asin;
+            unsafe {
+                native_asin = fix16_asin(raw);
+            }
+            assert_eq!(rusty_asin.0, native_asin);
+
+            let rusty_acos = Fix16(raw).acos();
+            let native_acos;
+            unsafe {
+                native_acos = fix16_acos(raw);
+            }
+            assert_eq!(rusty_acos.0, native_acos);
+        }
+
+        for raw in (i32::MIN..=i32::MAX).step_by(0xFFFF) {
+            let rusty_exp = Fix16(raw).exp();
+            let native_exp;
+            unsafe {
+                native_exp = fix16_exp(raw);
+            }
+            assert_eq!(rusty_exp.0, native_exp);
+
+            let rusty_ln = Fix16(raw).ln();
+            let native_ln;
+            unsafe {
+                native_ln = fix16_log(raw);
+            }
+            assert_eq!(rusty_ln.0, native_ln);
+
+            let rusty_log2 = Fix16(raw).log2();
+            let native_log2;
+            unsafe {
+                native_log2 = fix16_log2(raw);
+            }
+            assert_eq!(rusty_log2.0, native_log2);
+
+            let rusty_slog2 = Fix16(raw).saturating_log2();
+            let native_slog2;
+            unsafe {
+                native_slog2 = fix16_slog2(raw);
+            }
+            assert_eq!(rusty_slog2.0, native_slog2);
+        }
+
+        let third = Fract32::new(1, 3);
+
+        let rusty_invert = third.invert();
+        let native_invert;
+        unsafe {
+            native_invert = fract32_invert(third.0);
+        }
+        assert_eq!(rusty_invert.0, native_invert);
+
+        let rusty_usmul = third * 3_000_000_000u32;
+        let native_usmul;
+        unsafe {
+            native_usmul = fract32_usmul(3_000_000_000, third.0);
+        }
+        assert_eq!(rusty_usmul, native_usmul);
+
+        let rusty_smul = third * -2_000_000_000i32;
+        let native_smul;
+        unsafe {
+            native_smul = fract32_smul(-2_000_000_000, third.0);
+        }
+        assert_eq!(rusty_smul, native_smul);
     }
 
     #[test]
@@ -121,24 +275,24 @@ mod tests {
             -617.28
         );
 
-        let overflowing_operation = FIX16_MAX.overflowing_add(FIX16_ONE);
+        let overflowing_operation = Fix16::MAX.overflowing_add(Fix16::ONE);
         assert_eq!(overflowing_operation.1, true);
-        assert_eq!(overflowing_operation.0 - Fix16(FIX16_ONE.0 - 1), FIX16_MIN);
+        assert_eq!(overflowing_operation.0 - Fix16(Fix16::ONE.0 - 1), Fix16::MIN);
 
-        let underflowing_operation = FIX16_MIN.overflowing_sub(FIX16_ONE - Fix16(FIX16_ONE.0 - 1));
+        let underflowing_operation = Fix16::MIN.overflowing_sub(Fix16::ONE - Fix16(Fix16::ONE.0 - 1));
         assert_eq!(underflowing_operation.1, true);
-        assert_eq!(underflowing_operation.0, FIX16_MAX);
+        assert_eq!(underflowing_operation.0, Fix16::MAX);
 
-        let hex_str_parse = Fix16::from_hex_str(&"0x32.69".to_string());
+        let hex_str_parse = Fix16::from_hex_str("0x32.69");
         if let Err(why) = hex_str_parse {
-            panic!(why);
+            panic!("{}", why);
         }
         let hex_str_parse = hex_str_parse.unwrap();
         assert_eq!(hex_str_parse, Fix16(0x0032_6900));
 
-        let str_parse = Fix16::from_str(&"-69.420".to_string());
+        let str_parse = Fix16::from_str("-69.420");
         if let Err(why) = str_parse {
-            panic!(why);
+            panic!("{}", why);
         }
         let str_parse = str_parse.unwrap();
         assert_eq!(str_parse, Fix16::from(-69.420));
@@ -149,5 +303,882 @@ mod tests {
         assert_eq!(Fix16::from(203).sin(), Fix16(0xeee4));
         assert_eq!(Fix16::from(4203).cos(), Fix16(0xe758));
         assert_eq!(Fix16::from(2193).tan(), Fix16(0x2cac));
+
+        assert_eq!(Fix16::ONE.atan(), Fix16(0xc90f));
+        assert_eq!(Fix16(-Fix16::ONE.0).atan2(Fix16(-Fix16::ONE.0)), Fix16(-0x25b2f));
+        assert_eq!(Fix16(0x8000).asin(), Fix16(0x86b0));
+        assert_eq!(Fix16(0x8000).acos(), Fix16(0x10b6f));
+
+        assert_eq!(Fix16::from(-11).saturating_exp(), Fix16(1));
+        if !crate::no_rounding() {
+            assert_eq!(Fix16::from(2).exp(), Fix16::from(7.388977));
+            assert_eq!(Fix16::from(2).ln(), Fix16::from(0.693161));
+        }
+        assert_eq!(Fix16::from(0.5).log2(), Fix16::from(-1));
+        assert_eq!(Fix16::from(-1).saturating_ln(), Fix16::MIN);
+        assert_eq!(Fix16::from(2).pow(Fix16::from(10)), Fix16::from(1024));
+        assert_eq!(Fix16::from(-2).pow(Fix16::from(3)), Fix16::from(-8));
+        assert_eq!(Fix16::from(2).saturating_pow(Fix16::from(15)), Fix16::MAX);
+        assert_eq!(Fix16::from(-2).saturating_pow(Fix16::from(0.5)), Fix16::MIN);
+        assert_eq!(Fix16::from(2).pow(Fix16::from(-15)), Fix16(2));
+        assert_eq!(Fix16::from(-2).pow(Fix16::from(-15)), Fix16(-2));
+        assert_eq!(Fix16::from(2).pow(Fix16::from(-16)), Fix16(1));
+        assert_eq!(Fix16::from(2).pow(Fix16::from(-40)), Fix16(0));
+        if !crate::no_rounding() {
+            assert_eq!(Fix16::from(200).pow(Fix16::from(-2)), Fix16(2));
+        }
+    }
+
+    #[test]
+    fn fixed_formats() {
+        let q24_8 = Fixed::<8>::from(-1234.56);
+        let q8_24 = Fixed::<24>::from(3.3);
+
+        assert_eq!(q24_8, Fixed::<8>(-316047));
+        assert_eq!(q8_24, Fixed::<24>(55364812));
+
+        assert_eq!(q24_8 * Fixed::<8>::from(100), Fixed::<8>(-31604700));
+        assert_eq!(q8_24 / Fixed::<24>::from(2), Fixed::<24>(27682406));
+        assert_eq!(Fixed::<24>::from(2).sqrt(), Fixed::<24>(23726566));
+        if !crate::no_rounding() {
+            assert_eq!(Fix16::from(30000) / Fix16(0xF333), Fix16(2069564543));
+        }
+        assert!(Fixed::<8>::from(2000000).overflowing_mul(Fixed::<8>::from(2000)).1);
+
+        if !crate::no_rounding() {
+            assert_eq!(q8_24.rescale::<16>(), Fix16(216269));
+        }
+        assert_eq!(Fix16(0x8001).rescale::<8>(), Fixed::<8>(0x80));
+        assert_eq!(Fix16(-0x8080).rescale::<8>(), Fixed::<8>(-0x81));
+        assert_eq!(q24_8.lossless_rescale::<16>(), Some(Fix16(-316047 << 8)));
+        assert_eq!(Fix16(0x8001).lossless_rescale::<8>(), None);
+        assert_eq!(Fix16::MAX.lossless_rescale::<24>(), None);
+        assert!(Fix16::MAX.overflowing_rescale::<24>().1);
+        assert_eq!(Fix16::MAX.saturating_rescale::<24>(), Fixed::<24>(i32::MAX));
+        assert_eq!(Fix16::MIN.saturating_rescale::<24>(), Fixed::<24>(i32::MIN));
+
+        assert_eq!(format!("{:?}", Fix16(-0x8000)), "Fix16(-32768)");
+        assert_eq!(format!("{:?}", q24_8), "Fixed<8>(-316047)");
+        assert_eq!(format!("{:x?}", Fixed::<24>(0x100)), "Fixed<24>(100)");
+    }
+
+    #[test]
+    fn fine_format_division() {
+        let mode = if crate::no_rounding() {
+            RoundingMode::TowardZero
+        } else {
+            RoundingMode::NearestAway
+        };
+
+        assert_eq!(Fixed::<30>(84971) / Fixed::<30>(6761794), Fixed::<30>(13493004));
+
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as i32
+        };
+
+        for _ in 0..10000 {
+            // Numerators are kept small enough that the quotient always fits.
+            let (num, den) = (next(), next() & 0x3FFF_FFFF | 1 << 20);
+            let den = if num & 1 == 0 { den } else { -den };
+
+            let (a, b) = (Fixed::<24>(num >> 5), Fixed::<24>(den));
+            assert_eq!(a.overflowing_div(b), a.overflowing_div_round(b, mode));
+
+            let (a, b) = (Fixed::<30>(num >> 12), Fixed::<30>(den));
+            assert_eq!(a.overflowing_div(b), a.overflowing_div_round(b, mode));
+        }
+    }
+
+    #[test]
+    fn fix32() {
+        let big = Fix32::from(1_000_000);
+        let small = Fix32::from(0.125);
+
+        assert_eq!(big * small, Fix32::from(125_000));
+        assert_eq!(big / Fix32::from(3), Fix32(1_431_655_765_333_333));
+        assert_eq!(Fix32::from(-7) / Fix32::from(2), Fix32::from(-3.5));
+        assert!(big.overflowing_mul(big).1);
+        assert_eq!(big.saturating_mul(Fix32::from(-1_000_000)), Fix32(-0x80000000_00000000));
+        assert_eq!(FIX32_MAX.saturating_add(FIX32_ONE), FIX32_MAX);
+
+        if !crate::no_rounding() {
+            // Only rounding up carries this product past the maximum.
+            let (a, b) = (Fix32(9223372034707292160), Fix32(4294967297));
+            assert_eq!(a.overflowing_mul(b), (FIX32_MIN, true));
+            assert_eq!(a.saturating_mul(b), FIX32_MAX);
+
+            assert_eq!(Fix32::from(2).sqrt(), Fix32(6_074_001_000));
+        }
+        assert_eq!(Fix32::from(1_000_000).sqrt(), Fix32::from(1000));
+
+        for x in [-1000.0f64, -3.0, -0.5, 0.0, 0.25, 1.0, 2.0, 100.0, 123_456.0].iter() {
+            let angle = Fix32::from(*x);
+            assert!((f64::from(angle.sin()) - x.sin()).abs() < 1e-9);
+            assert!((f64::from(angle.cos()) - x.cos()).abs() < 1e-9);
+            assert!((f64::from(angle.tan()) - x.tan()).abs() < 1e-6);
+        }
+        assert_eq!((FIX32_PI / Fix32::from(2)).sin(), FIX32_ONE);
+
+        assert_eq!(Fix32::from_hex_str("0x32.69"), Ok(Fix32(0x32_6900_0000)));
+        assert_eq!(Fix32::from_hex_str("-0X1.8"), Ok(Fix32::from(-1.5)));
+        assert!(Fix32::from_hex_str("0x1.123456789").is_err());
+        assert_eq!(Fix32::from_str("-69.420"), Ok(Fix32(-298_156_629_688)));
+        assert_eq!(Fix32::from_str("2147483647.9999999998"), Ok(FIX32_MAX));
+        assert!(Fix32::from_str("2147483648").is_err());
+        assert!(Fix32::from_str("1.2.3").is_err());
+
+        assert_eq!(Fix32::from(Fix16(0x0032_6900)), Fix32(0x32_6900_0000));
+        if !crate::no_rounding() {
+            assert_eq!(Fix16::try_from(Fix32(0x32_6900_8000)), Ok(Fix16(0x0032_6901)));
+        }
+        assert!(Fix16::try_from(big).is_err());
+        assert_eq!(i64::from(Fix32::from(-2.5)), -3);
+    }
+
+    #[test]
+    fn ufix16() {
+        let big = UFix16::from(60000);
+        let half = UFix16::from(0.5);
+
+        assert_eq!(big + UFix16::from(5000), UFix16::from(65000));
+        assert_eq!(big * half, UFix16::from(30000));
+        assert_eq!(UFix16::from(30000) / half, big);
+        assert_eq!(UFix16::from(1) / UFix16::from(3), UFix16(0x5555));
+        if !crate::no_rounding() {
+            // A divisor above 32768.0 doesn't fit libfixmath's division loop.
+            assert_eq!(UFix16(0xfb9d_dd3d) / UFix16(0x8134_09c7), UFix16(0x1_f28c));
+        }
+        assert!(big.overflowing_mul(UFix16::from(2)).1);
+        assert!(big.overflowing_div(half).1);
+        assert_eq!(big.saturating_add(big), UFIX16_MAX);
+        assert_eq!(half.saturating_sub(UFIX16_ONE), UFix16(0));
+        assert_eq!(big.saturating_div(UFix16(1)), UFIX16_MAX);
+
+        assert_eq!(UFix16::from(22).sqrt(), UFix16(0x4b0bf));
+        if !crate::no_rounding() {
+            assert_eq!(UFIX16_MAX.sqrt(), UFix16(0x1000000));
+            assert_eq!(u32::from(UFix16::from(2.5)), 3);
+        }
+
+        assert_eq!(UFix16::try_from(Fix16::from(-1)), Err(TryFromFixedError(())));
+        assert_eq!(UFix16::try_from(Fix16::from(2)), Ok(UFix16::from(2)));
+        assert!(Fix16::try_from(big).is_err());
+        assert_eq!(Fix16::try_from(half), Ok(Fix16::from(0.5)));
+    }
+
+    #[test]
+    fn fract32() {
+        let third = Fract32::new(1, 3);
+        let quarter = Fract32::new(250, 1000);
+
+        assert_eq!(third, Fract32(0x55555555));
+        assert_eq!(quarter, Fract32(0x40000000));
+        assert_eq!(Fract32::new(3, 3), FRACT32_MAX);
+        assert_eq!(Fract32::new(1, 0), FRACT32_MAX);
+        assert_eq!(quarter.invert(), Fract32(0xBFFFFFFF));
+
+        assert_eq!(third * 3_000_000_000u32, 999_999_999);
+        assert_eq!(quarter * -2_000_000_000i32, -500_000_000);
+        assert_eq!(quarter * Fix16::from(-1234), Fix16::from(-308.5));
+        assert_eq!(Fix16::from(100) * third, Fix16(0x215555));
+        assert_eq!(Fix16::from(third), Fix16(0x5555));
+        if !crate::no_rounding() {
+            assert_eq!(Fix16::from(FRACT32_MAX), Fix16::ONE);
+        }
+    }
+
+    #[test]
+    fn checked_ops() {
+        let two = Fix16::from(2);
+
+        assert_eq!(two.checked_add(two), Some(Fix16::from(4)));
+        assert_eq!(Fix16::MAX.checked_add(Fix16::ONE), None);
+        assert_eq!(Fix16::MIN.checked_sub(Fix16::ONE), None);
+        assert_eq!(two.checked_mul(Fix16::from(-3)), Some(Fix16::from(-6)));
+        assert_eq!(Fix16::from(20000).checked_mul(two), None);
+        if !crate::no_rounding() {
+            // Only rounding up carries this product past the maximum.
+            assert_eq!(Fix16(2147450880).checked_mul(Fix16(65537)), None);
+            assert_eq!(Fix16(2147450880).saturating_mul(Fix16(65537)), Fix16::MAX);
+            assert_eq!((Wrapping(Fix16(2147450880)) * Wrapping(Fix16(65537))).0, Fix16::MIN);
+        }
+        assert_eq!(two.checked_div(Fix16::from(-4)), Some(Fix16::from(-0.5)));
+        assert_eq!(two.checked_div(Fix16(0)), None);
+        assert_eq!(Fix16::from(20000).checked_div(Fix16::from(0.5)), None);
+        assert_eq!(Fix16::MIN.checked_div(Fix16::ONE), Some(Fix16::MIN));
+        assert_eq!(Fix16::MAX.checked_div(-Fix16::ONE), Some(-Fix16::MAX));
+        assert_eq!(Fix16::MIN.checked_div(-Fix16::ONE), None);
+        assert_eq!(Fix16::from(7).checked_rem(two), Some(Fix16::ONE));
+        assert_eq!(two.checked_rem(Fix16(0)), None);
+        assert_eq!(Fix16::MIN.checked_rem(Fix16(-1)), Some(Fix16::MIN % Fix16(-1)));
+        assert_eq!(Fix16::MIN.checked_rem(Fix16(-1)), Some(Fix16(0)));
+        assert_eq!(two.checked_neg(), Some(Fix16::from(-2)));
+        assert_eq!(Fix16::MIN.checked_neg(), None);
+        assert_eq!(Fix16::from(-2).checked_abs(), Some(two));
+        assert_eq!(Fix16::MIN.checked_abs(), None);
+        assert_eq!(Fix16::from(4).checked_sqrt(), Some(two));
+        assert_eq!(Fix16::from(-4).checked_sqrt(), None);
+
+        assert_eq!(two.overflowing_div(Fix16(0)), (Fix16::MIN, true));
+        assert_eq!(two.saturating_div(Fix16(0)), Fix16::MAX);
+        assert_eq!(Fix16::from(-2).saturating_div(Fix16(0)), Fix16::MIN);
+    }
+
+    #[test]
+    fn overflow_wrappers() {
+        let big = Saturating(Fix16::from(20000));
+        let neg = Saturating(Fix16::from(-3));
+
+        assert_eq!((big + big).0, Fix16::MAX);
+        assert_eq!((big * neg + big).0, Fix16::from(-12768));
+        assert_eq!((big * neg - big - big).0, Fix16::MIN);
+        assert_eq!((big / Saturating(Fix16(0))).0, Fix16::MAX);
+        assert_eq!((neg % Saturating(Fix16::from(2))).0, Fix16::from(-1));
+
+        let mut acc = Saturating(Fix32::from(1));
+        acc *= Saturating(Fix32::from(-65536));
+        acc *= Saturating(Fix32::from(65536));
+        assert_eq!(acc.0, Fix32(-0x80000000_00000000));
+
+        let mut level = Saturating(UFix16::from(1));
+        level -= Saturating(UFix16::from(2));
+        assert_eq!(level.0, UFix16(0));
+
+        let max = Wrapping(Fix16::MAX);
+        let one = Wrapping(Fix16::ONE);
+
+        assert_eq!((max + one).0, Fix16(Fix16::MIN.0 + 0xFFFF));
+        assert_eq!((Wrapping(Fix16::MIN) - one).0, Fix16(Fix16::MAX.0 - 0xFFFF));
+        assert_eq!((Wrapping(Fix16::from(-2)) * Wrapping(Fix16::from(2))).0, Fix16::from(-4));
+        assert_eq!((Wrapping(Fix16::MIN) % Wrapping(Fix16(-1))).0, Fix16(0));
+
+        let mut wrapped = Wrapping(UFix16::from(65535));
+        wrapped += Wrapping(UFix16::from(2));
+        assert_eq!(wrapped.0, UFix16::from(1));
+    }
+
+    #[test]
+    fn operator_overflow_policy() {
+        fn check<T: PartialEq + std::fmt::Debug>(
+            op: impl FnOnce() -> T + std::panic::UnwindSafe,
+            sentinel: T,
+        ) {
+            match std::panic::catch_unwind(op) {
+                Ok(res) => {
+                    assert!(!crate::overflow_checks());
+                    assert_eq!(res, sentinel);
+                }
+                Err(_) => assert!(crate::overflow_checks()),
+            }
+        }
+
+        check(|| Fix16::MAX + Fix16::ONE, FIX16_OVERFLOW);
+        check(|| Fix16::MIN - Fix16::ONE, FIX16_OVERFLOW);
+        check(|| Fix16::MAX * Fix16::from(2), FIX16_OVERFLOW);
+        check(|| Fix16::ONE / Fix16(0), FIX16_OVERFLOW);
+        check(|| Fix16::ONE % Fix16(0), FIX16_OVERFLOW);
+        check(|| -Fix16::MIN, FIX16_OVERFLOW);
+        check(|| FIX32_MAX + FIX32_ONE, FIX32_OVERFLOW);
+        check(|| FIX32_ONE / Fix32(0), FIX32_OVERFLOW);
+        check(|| UFix16(0) - UFIX16_ONE, UFIX16_OVERFLOW);
+        check(|| UFIX16_MAX * UFix16::from(2), UFIX16_OVERFLOW);
+
+        assert_eq!(Fix16::MIN % Fix16(-1), Fix16(0));
+        assert_eq!(Fix16::MIN / Fix16::ONE, Fix16::MIN);
+
+        // Internal math keeps libfixmath's sentinel semantics either way.
+        assert_eq!(Fix16(0).atan2(Fix16(0)), Fix16(1686685967));
+        assert_eq!(Fix16(2).log2(), Fix16(0));
+    }
+
+    #[test]
+    fn rounding_modes() {
+        use RoundingMode::*;
+
+        let modes = [NearestAway, NearestEven, TowardZero, Floor, Ceil];
+        let half = Fix16(0x8000);
+
+        for (mode, expected) in modes.iter().zip(&[
+            [1, 0, 2, -1, 0, -21845, 92682, 3, -3],
+            [0, 0, 2, 0, 0, -21845, 92682, 2, -2],
+            [0, 0, 1, 0, 0, -21845, 92681, 2, -2],
+            [0, 0, 1, -1, 0, -21846, 92681, 2, -3],
+            [1, 1, 2, 0, 1, -21845, 92682, 3, -2],
+        ]) {
+            assert_eq!(Fix16(1).mul_round(half, *mode).0, expected[0]);
+            assert_eq!(Fix16(0).mul_round(half, *mode).0, 0);
+            assert_eq!(Fix16(3).mul_round(half, *mode).0, expected[2]);
+            assert_eq!(Fix16(-1).mul_round(half, *mode).0, expected[3]);
+            assert_eq!(Fix16(1).div_round(Fix16::from(3), *mode).0, expected[4]);
+            assert_eq!(Fix16::from(-1).div_round(Fix16::from(3), *mode).0, expected[5]);
+            assert_eq!(Fix16::from(2).sqrt_round(*mode).0, expected[6]);
+            assert_eq!(Fix16::from(-2).sqrt_round(*mode).0, -expected[6] - (*mode == Floor) as i32 + (*mode == Ceil) as i32);
+            assert_eq!(Fix16::from(4).sqrt_round(*mode), Fix16::from(2));
+            assert_eq!(Fixed::<1>::from_f32_round(1.25, *mode).0, expected[7]);
+            assert_eq!(Fixed::<1>::from_f32_round(-1.25, *mode).0, expected[8]);
+            assert_eq!(Fix16::from(2.5).to_i32_round(*mode), expected[7]);
+            assert_eq!(Fix16::from(-2.5).to_i32_round(*mode), expected[8]);
+            assert_eq!(Fix16(1).overflowing_div_round(Fix16(0), *mode), (Fix16::MIN, true));
+            assert!(Fix16::MAX.overflowing_mul_round(Fix16::from(2), *mode).1);
+        }
+
+        assert_eq!(Fix16::from(3.5).to_i32_round(NearestEven), 4);
+        assert_eq!(Fix16::from_f32_round(1e10, Floor), Fix16::MAX);
+        assert_eq!(Fix16::from_f32_round(f32::NAN, Ceil), Fix16(0));
+
+        // The operators keep rounding to nearest with ties away from zero. Large
+        // divisors take libfixmath's inexact estimate path, so skip those.
+        if !crate::no_rounding() {
+            for a in (-0x7FFF_FFFF..0x7FFF_FFFF).step_by(0x7654321) {
+                for b in (-0x1_0000_0000i64..0x1_0000_0000).step_by(0x3210_0123) {
+                    let (a, b) = (Fix16(a), Fix16((b >> 10) as i32));
+
+                    assert_eq!(a.overflowing_mul(b), a.overflowing_mul_round(b, NearestAway));
+                    if b.0 != 0 && b.0.unsigned_abs() < 0x10_0000 {
+                        assert_eq!(a.overflowing_div(b), a.overflowing_div_round(b, NearestAway));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn decimal_parsing() {
+        let parse = Fix16::from_str;
+
+        assert_eq!(parse("32767.99999"), Ok(Fix16::MAX));
+        assert!(parse("32768").is_err());
+        assert_eq!(parse("-32768.000007"), Ok(Fix16::MIN));
+        assert!(parse("-32768.00002").is_err());
+        assert!(parse("100000000000000000000000000000000000000000").is_err());
+
+        assert_eq!(parse("+.5"), Ok(Fix16(0x8000)));
+        assert_eq!(parse("5."), Ok(Fix16::from(5)));
+        assert_eq!(parse("-0"), Ok(Fix16(0)));
+
+        if !crate::no_rounding() {
+            // f32 only has 24 bits of mantissa, which would give 0x75304D00.
+            assert_eq!(parse("30000.3"), Ok(Fix16(0x7530_4CCD)));
+            assert!(parse("32767.999993").is_err());
+
+            // 2^-17 is exactly half an ulp.
+            assert_eq!(parse("0.00000762939453125"), Ok(Fix16(1)));
+            assert_eq!(parse("-0.00000762939453125"), Ok(Fix16(-1)));
+            assert_eq!(parse("0.00000762939453124999999999999999999"), Ok(Fix16(0)));
+            assert_eq!(
+                Fix32::from_str("0.000000000116415321826934814453125"),
+                Ok(Fix32(1))
+            );
+            assert_eq!(
+                Fix32::from_str("0.000000000116415321826934814453124"),
+                Ok(Fix32(0))
+            );
+        }
+
+        for bad in &["", "-", ".", "1.2.3", "12a", "1e5", " 1"] {
+            assert!(parse(bad).is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn decimal_formatting() {
+        assert_eq!(Fix16(1).to_string(), "0.00002");
+        assert_eq!(Fix16::MIN.to_string(), "-32768");
+        assert_eq!(Fix16::from(-1.5).to_string(), "-1.5");
+        assert_eq!(format!("{:.3}", Fix16::PI), "3.142");
+        assert_eq!(format!("{:.0}", Fix16::from(-2.5)), "-3");
+        assert_eq!(format!("{:.2}", Fix16::from(9.999)), "10.00");
+        assert_eq!(format!("{:.20}", Fix16(1)), "0.00001525878906250000");
+        assert_eq!(format!("{:#}", Fix16(1)), "0.0000152587890625");
+        assert_eq!(format!("{:#}", Fix16::from(2)), "2.0000000000000000");
+        assert_eq!(
+            format!("{:>+8}|{:<6}|{:06}", Fix16::ONE, Fix16(0x8000), Fix16::from(-2)),
+            "      +1|0.5   |-00002"
+        );
+        #[cfg(feature = "std")]
+        assert_eq!(Fix16::from(-69.42).to_str(4), "-69.4200");
+
+        assert_eq!(format!("{:#}", Fix32(1)), "0.00000000023283064365386962890625");
+
+        // The shortest form depends on how the parser rounds.
+        if !crate::no_rounding() {
+            assert_eq!(Fix16::MAX.to_string(), "32767.99998");
+            assert_eq!(UFIX16_MAX.to_string(), "65535.99998");
+            assert_eq!(FIX32_PI.to_string(), "3.1415926537");
+        }
+
+        // Every value must survive a round trip through the shortest form.
+        for bits in (i32::MIN..=i32::MAX).step_by(0x10001) {
+            let val = Fix16(bits);
+            assert_eq!(Fix16::from_str(&val.to_string()), Ok(val));
+        }
+        for bits in (i64::MIN..=i64::MAX).step_by(0x1234_5678_9ABC) {
+            assert_eq!(Fix32::from_str(&Fix32(bits).to_string()), Ok(Fix32(bits)));
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        use ParseFixedError::*;
+
+        assert_eq!("".parse::<Fix16>(), Err(Empty));
+        assert_eq!(Fix16::try_from("-."), Err(Empty));
+        assert_eq!(Fix16::from_hex_str(""), Err(Empty));
+        assert_eq!(Fix16::from_hex_str("-0x"), Err(Empty));
+        assert_eq!("1.2.3".parse::<Fix16>(), Err(InvalidDigit { pos: 3 }));
+        assert_eq!("-0x1g".parse::<Fix16>(), Err(InvalidDigit { pos: 4 }));
+        assert_eq!("12 ".parse::<Fix16>(), Err(InvalidDigit { pos: 2 }));
+        assert_eq!("1é".parse::<Fix16>(), Err(InvalidDigit { pos: 1 }));
+        assert_eq!("32768".parse::<Fix16>(), Err(Overflow));
+        assert_eq!(Fix16::from_hex_str("0x8000"), Err(Overflow));
+        assert_eq!(Fix16::from_hex_str("0x1.00008"), Err(FractionTooLong));
+        assert_eq!(Fixed::<2>::from_hex_str("0x1.6"), Err(FractionTooLong));
+
+        assert_eq!(Fix16::from_hex_str("-0x8000"), Ok(Fix16::MIN));
+        assert_eq!(Fix16::from_hex_str("1.8"), Ok(Fix16::from(1.5)));
+        assert_eq!(Fix16::from_hex_str("0X7fff.ffff000"), Ok(Fix16::MAX));
+        assert_eq!(Fixed::<2>::from_hex_str("0x1.c"), Ok(Fixed::<2>(7)));
+        assert_eq!("-0x1.8".parse::<Fix16>(), Ok(Fix16::from(-1.5)));
+        assert_eq!(Fix16::try_from("+2.25"), Ok(Fix16::from(2.25)));
+
+        assert_eq!(Fix32::try_from("0x1.000000008"), Err(FractionTooLong));
+        assert_eq!("-2147483648".parse::<Fix32>(), Ok(Fix32::from(i32::MIN)));
+        assert_eq!(Overflow.to_string(), "number too large to fit in target type");
+    }
+
+    #[test]
+    fn radix_formatting() {
+        assert_eq!(format!("{:x}", Fix16(0x0032_6900)), "32.69");
+        assert_eq!(format!("{:#x}", Fix16(0x0032_6900)), "0x32.69");
+        assert_eq!(format!("{:X}", Fix16(0x00AB_CD00)), "AB.CD");
+        assert_eq!(format!("{:#b}", Fix16::from(3.25)), "0b11.01");
+        assert_eq!(format!("{:o}", Fix16::from(8.5)), "10.4");
+        assert_eq!(format!("{:#o}", Fix16(1)), "0o0.000004");
+        assert_eq!(format!("{:#x}", Fix16::from(-1.5)), "-0x1.8");
+        assert_eq!(format!("{:x}", Fix16::MIN), "-8000");
+        assert_eq!(format!("{:#08x}", Fix16::from(1.5)), "0x0001.8");
+        assert_eq!(format!("{:#X}", FIX32_MAX), "0x7FFFFFFF.FFFFFFFF");
+        assert_eq!(format!("{:b}", UFix16(0x8000_8000)), "1000000000000000.1");
+
+        assert_eq!(Fix16::from_radix_str("-0b11.01", 2), Ok(Fix16::from(-3.25)));
+        assert_eq!(Fix16::from_radix_str("0o10.4", 8), Ok(Fix16::from(8.5)));
+        assert_eq!(
+            Fix16::from_radix_str("10.2", 2),
+            Err(ParseFixedError::InvalidDigit { pos: 3 })
+        );
+        assert_eq!("0b0.1".parse::<Fix16>(), Ok(Fix16(0x8000)));
+        assert_eq!("-0o7".parse::<Fix32>(), Ok(Fix32::from(-7)));
+
+        for bits in (i32::MIN..=i32::MAX).step_by(0x1_0203) {
+            let val = Fix16(bits);
+
+            for (radix, str) in &[
+                (16, format!("{:x}", val)),
+                (16, format!("{:#X}", val)),
+                (8, format!("{:o}", val)),
+                (2, format!("{:#b}", val)),
+            ] {
+                assert_eq!(Fix16::from_radix_str(str, *radix), Ok(val));
+            }
+
+            assert_eq!(format!("{:#o}", val).parse::<Fix16>(), Ok(val));
+            assert_eq!(
+                Fixed::<5>::from_radix_str(&format!("{:o}", Fixed::<5>(bits)), 8),
+                Ok(Fixed::<5>(bits))
+            );
+        }
+
+        for bits in (i64::MIN..=i64::MAX).step_by(0x1234_5678_9ABC) {
+            let val = Fix32(bits);
+            assert_eq!(Fix32::from_radix_str(&format!("{:o}", val), 8), Ok(val));
+            assert_eq!(format!("{:#x}", val).parse::<Fix32>(), Ok(val));
+        }
+    }
+
+    #[test]
+    fn buffer_formatting() {
+        let mut buf = [0u8; 16];
+
+        assert_eq!(Fix16::from(-69.42).to_str_buf(&mut buf, 4), Ok("-69.4200"));
+        assert_eq!(Fix16::MIN.format_into(&mut buf), Ok("-32768"));
+        assert_eq!(Fix32(1).format_into(&mut buf[..8]), Err(core::fmt::Error));
+        assert_eq!(UFix16::from(7).format_into(&mut buf[..1]), Ok("7"));
+        assert!(Fix16(1).to_str_buf(&mut buf, 16).is_err());
+
+        assert_eq!(Fix16::from_bytes(b"-1.25"), Ok(Fix16::from(-1.25)));
+        assert_eq!(Fix16::from_bytes(b"0x1.8\n"), Err(ParseFixedError::InvalidDigit { pos: 5 }));
+        assert_eq!(Fix32::from_bytes(&[b'1', 0xFF]), Err(ParseFixedError::InvalidDigit { pos: 1 }));
+
+        // Zeros past the exact digits are written without a buffer.
+        let wide = format!("{:>+50.40}", Fix16(0x8000));
+        assert_eq!(wide, format!("{:>50}", format!("+0.5{}", "0".repeat(39))));
+        assert_eq!(format!("{:^9x}|{:*<10}", Fix16::ONE, Fix16(-1)), "    1    |-0.00002**");
+    }
+
+    #[test]
+    fn const_construction() {
+        const TABLE: [Fix16; 4] = [
+            Fix16::from_int(-3),
+            Fix16::from_bits(0x18000),
+            fix16!("1.2345"),
+            fix16!(-0.00001),
+        ];
+
+        assert_eq!(TABLE[0], Fix16::from(-3));
+        assert_eq!(TABLE[1].to_bits(), 0x18000);
+        assert_eq!(TABLE[2], "1.2345".parse().unwrap());
+        assert_eq!(TABLE[3], Fix16::from_str("-0.00001").unwrap());
+
+        assert_eq!(fix16!(1.2345), TABLE[2]);
+        assert_eq!(fix16!(32767.99999), Fix16::MAX);
+        assert_eq!(fix16!("-32768"), Fix16::MIN);
+        assert_eq!(fix16!("0x1.8"), Fix16(0x18000));
+        assert_eq!(fix16!(7), Fix16::from(7));
+        assert_eq!(fix16!(1_000.5), Fix16(1000 << 16 | 0x8000));
+        assert_eq!(fix16!(-0.000_01), fix16!(-0.00001));
+        assert_eq!(fix16!(0x7f_ff), Fix16::from(0x7fff));
+        // Separators are only skipped in numeric literals, like Rust does.
+        assert!(std::panic::catch_unwind(|| fix16::from_literal("\"1_000\"")).is_err());
+
+        const HALF: Result<Fix16, ParseFixedError> = Fix16::from_bytes(b"0.5");
+        assert_eq!(HALF, Ok(Fix16(0x8000)));
+    }
+
+    #[test]
+    fn const_arithmetic() {
+        const SQRT2: Fix16 = Fix16::from_int(2).sqrt();
+        const INV_SQRT2: Fix16 = Fix16::ONE.const_div(SQRT2);
+        const TABLE: [Fix16; 4] = [
+            SQRT2.const_mul(INV_SQRT2),
+            SQRT2.const_add(INV_SQRT2).floor(),
+            Fix16::PI.const_sub(SQRT2).ceil(),
+            Fix16::PI.const_rem(SQRT2).abs().max(INV_SQRT2),
+        ];
+
+        assert_eq!(SQRT2, Fix16::from(2).sqrt());
+        assert_eq!(INV_SQRT2, Fix16::ONE / Fix16::from(2).sqrt());
+        assert_eq!(TABLE[0], SQRT2 * INV_SQRT2);
+        assert_eq!(TABLE[1], (SQRT2 + INV_SQRT2).floor());
+        assert_eq!(TABLE[2], (Fix16::PI - SQRT2).ceil());
+        assert_eq!(TABLE[3], (Fix16::PI % SQRT2).max(INV_SQRT2));
+
+        for &(a, b) in &[(Fix16(0x1234_5678), Fix16(0x3_0000)), (Fix16(-0x4000), Fix16(1)), (Fix16(-0x4000_0000), Fix16(0x1_8000))] {
+            assert_eq!(a.const_mul(b), a * b);
+            assert_eq!(a.const_div(b), a / b);
+            assert_eq!(a.const_rem(b), a % b);
+        }
+
+        const SATURATED: Fix16 = Fix16::MAX.saturating_mul(Fix16::from_int(2));
+        const CHECKED: Option<Fix16> = Fix16::MIN.checked_neg();
+        assert_eq!(SATURATED, Fix16::MAX);
+        assert_eq!(CHECKED, None);
+    }
+
+    #[test]
+    fn primitive_conversions() {
+        let range = Err(TryFromFixedError(()));
+
+        assert_eq!(Fix16::try_from(-128i8), Ok(Fix16::from(-128)));
+        assert_eq!(Fix16::try_from(32767i64), Ok(Fix16::from(32767)));
+        assert_eq!(Fix16::try_from(32768i64), range);
+        assert_eq!(Fix16::try_from(-32768isize), Ok(Fix16::MIN));
+        assert_eq!(Fix16::try_from(32768u16), range);
+        assert_eq!(Fix16::try_from(u64::MAX), range);
+        assert_eq!(Fix16::try_from(255u8), Ok(Fix16::from(255)));
+        assert_eq!(Fixed::<30>::try_from(2u32), Err(TryFromFixedError(())));
+        assert_eq!(Fixed::<30>::try_from(-2i16), Ok(Fixed::<30>(i32::MIN)));
+
+        assert_eq!(Fix16::from_i32_saturating(i32::MAX), Fix16::MAX);
+        assert_eq!(Fix16::checked_from_int(40000), None);
+        assert_eq!(Fix16::checked_from_int(-32768), Some(Fix16::MIN));
+        assert_eq!(Fixed::<30>::checked_from_int(1), Some(Fixed::<30>(1 << 30)));
+        assert_eq!(Fixed::<30>::checked_from_int(2), None);
+        assert_eq!(Fix16::from_i64_saturating(i64::MIN), Fix16::MIN);
+        assert_eq!(Fix16::from_u64_saturating(u64::MAX), Fix16::MAX);
+        assert_eq!(Fix16::from_usize_saturating(12), Fix16::from(12));
+        assert_eq!(Fixed::<24>::from_i8_saturating(-128), Fixed::<24>(i32::MIN));
+
+        assert_eq!(Fix16::try_from(1.5f64), Ok(Fix16(0x18000)));
+        assert_eq!(Fix16::try_from(32767.99999f64), Ok(Fix16::MAX));
+        assert_eq!(Fix16::try_from(32768f64), range);
+        assert_eq!(Fix16::try_from(-32768.000007f64), Ok(Fix16::MIN));
+        assert_eq!(Fix16::try_from(f64::NAN), range);
+        assert_eq!(Fix16::from_f64_saturating(1e10), Fix16::MAX);
+        assert_eq!(Fix16::from_f64_saturating(f64::NEG_INFINITY), Fix16::MIN);
+        assert_eq!(Fix16::from_f64_saturating(f64::NAN), Fix16(0));
+        assert_eq!(f64::from(Fix16(-0x18000)), -1.5);
+
+        let val = Fix16::from(-2.5);
+        assert_eq!(val.to_int_round::<i8>(RoundingMode::NearestEven), Ok(-2));
+        assert_eq!(val.to_int_round::<i64>(RoundingMode::Floor), Ok(-3));
+        assert_eq!(val.to_int_round::<u8>(RoundingMode::Ceil), Err(TryFromFixedError(())));
+        assert_eq!(Fix16(-1).to_int_round::<u32>(RoundingMode::TowardZero), Ok(0));
+        assert_eq!(Fix16::MAX.to_int_round::<i16>(RoundingMode::NearestAway), Err(TryFromFixedError(())));
+        assert_eq!(Fix16::MAX.to_int_round::<u16>(RoundingMode::NearestAway), Ok(32768));
+    }
+
+    #[test]
+    fn hyperbolic() {
+        // The documented bounds, in ulps up to 1 and relative above. They
+        // assume rounding, truncation adds up to a few ulps.
+        type Case = (fn(Fix16) -> Fix16, fn(f64) -> f64, f64, f64);
+        let fns: [Case; 6] = [
+            (Fix16::sinh, f64::sinh, 3.0, 4e-5),
+            (Fix16::cosh, f64::cosh, 0.0, 3e-5),
+            (Fix16::tanh, f64::tanh, 2.0, 0.0),
+            (Fix16::asinh, f64::asinh, 3.0, 4e-5),
+            (Fix16::acosh, f64::acosh, 4.0, 4e-5),
+            (Fix16::atanh, f64::atanh, 2.0, 2e-5),
+        ];
+        let inputs = (i32::MIN..=i32::MAX).step_by(65521).chain((-0xC_0000..0xC_0000).step_by(61));
+
+        for bits in inputs.filter(|_| !crate::no_rounding()) {
+            let x = Fix16(bits);
+
+            for &(f, reference, ulps, rel) in &fns {
+                let (res, exact) = (f(x), reference(f64::from(x)));
+
+                if exact.abs() <= 1.0 {
+                    assert!((f64::from(res) - exact).abs() * 65536.0 <= ulps, "{:?}", x);
+                } else if exact.abs() < 32767.5 {
+                    assert!((f64::from(res) - exact).abs() <= exact.abs() * rel, "{:?}", x);
+                } else if exact > 32768.5 {
+                    assert_eq!(res, Fix16::MAX);
+                } else if exact < -32768.5 {
+                    assert_eq!(res, Fix16::MIN);
+                }
+            }
+        }
+
+        assert_eq!(Fix16::MIN.sinh(), Fix16::MIN);
+        assert_eq!(Fix16::MAX.cosh(), Fix16::MAX);
+        assert_eq!(Fix16::ZERO.cosh(), Fix16::ONE);
+        assert_eq!(Fix16::MIN.tanh(), -Fix16::ONE);
+        assert_eq!(Fix16::from(-5).atanh(), Fix16::MIN);
+        assert_eq!(Fix16::from(-5).acosh(), Fix16::ZERO);
+        assert!((Fix16::MIN.asinh().0 + 726817).abs() <= 4);
+    }
+
+    #[test]
+    fn interpolation() {
+        let pairs = [
+            (Fix16::from(-3), Fix16::from(5)),
+            (Fix16::MIN, Fix16::MAX),
+            (Fix16::MAX, Fix16::MIN),
+            (Fix16(12345), Fix16(-987654)),
+        ];
+
+        for &(a, b) in &pairs {
+            for &f in &[0u32, 1, 0x7F, 0x80, 0xFF, 0x8000, 0xFFFF, 0x8000_0000, 0xFFFF_FFFF, 0x1234_5678] {
+                let native_lerp8;
+                let native_lerp16;
+                let native_lerp32;
+                unsafe {
+                    native_lerp8 = fix16_lerp8(a.0, b.0, f as u8);
+                    native_lerp16 = fix16_lerp16(a.0, b.0, f as u16);
+                    native_lerp32 = fix16_lerp32(a.0, b.0, f);
+                }
+                assert_eq!(a.lerp8(b, f as u8).0, native_lerp8);
+                assert_eq!(a.lerp16(b, f as u16).0, native_lerp16);
+                assert_eq!(a.lerp32(b, f).0, native_lerp32);
+            }
+
+            assert_eq!(a.lerp_precise(b, Fix16::ZERO), a);
+            assert_eq!(a.lerp_precise(b, Fix16::ONE), b);
+            assert_eq!(a.lerp8_precise(b, u8::MAX), b);
+            assert_eq!(a.lerp16_precise(b, u16::MAX), b);
+            assert_eq!(a.lerp32_precise(b, u32::MAX), b);
+            assert_eq!(a.lerp32_precise(b, 0), a);
+            assert_eq!(a.inverse_lerp_precise(b, a), Fix16::ZERO);
+            assert_eq!(a.inverse_lerp_precise(b, b), Fix16::ONE);
+            assert_eq!(a.remap_precise((a, b), (b, a)), b);
+        }
+
+        let (a, b) = (Fix16::from(-3), Fix16::from(5));
+        assert_eq!(a.lerp(b, Fix16(0x8000)), Fix16::from(1));
+        assert_eq!(a.lerp(b, Fix16::ONE), b);
+        assert_eq!(a.lerp_precise(b, Fix16(0x8000)), Fix16::from(1));
+        assert_eq!(a.lerp_precise(b, Fix16::from(-2)), Fix16::from(-19));
+        assert_eq!(a.lerp_precise(b, Fix16::from(10000)), Fix16::MAX);
+        assert_eq!(a.lerp8_precise(b, 0x80), Fix16(0x1_0404));
+        assert_eq!(a.inverse_lerp(b, Fix16::from(1)), Fix16(0x8000));
+        assert_eq!(a.inverse_lerp_precise(b, Fix16::from(-7)), Fix16(-0x8000));
+        assert_eq!(a.inverse_lerp_precise(a, Fix16::from(1)), Fix16::ZERO);
+        assert_eq!(Fix16::MIN.inverse_lerp_precise(Fix16::MAX, Fix16::ZERO), Fix16(0x8000));
+
+        // The precise variants round like division, which truncates when not
+        // rounding.
+        let third = Fix16::from(3);
+        assert_eq!(Fix16::ZERO.inverse_lerp_precise(third, Fix16(-1)), Fix16::ZERO.inverse_lerp(third, Fix16(-1)));
+        assert_eq!(Fix16(-1).remap_precise((Fix16::ZERO, third), (Fix16::ZERO, Fix16::ONE)), Fix16(-1) / third);
+
+        assert_eq!(Fix16::from(1).remap((a, b), (Fix16::ZERO, Fix16::from(100))), Fix16::from(50));
+        assert_eq!(Fix16::from(1).remap_precise((a, b), (Fix16::ZERO, Fix16::from(100))), Fix16::from(50));
+        assert_eq!(Fix16::ZERO.remap_precise((Fix16::MIN, Fix16::MAX), (Fix16::from(-1), Fix16::ONE)), Fix16(0));
+        assert_eq!(Fix16::ONE.remap_precise((a, a), (b, a)), b);
+        assert_eq!(Fix16(1).remap_precise((Fix16::ZERO, Fix16::from(3)), (Fix16::ZERO, Fix16::ONE)), Fix16(0));
+        if !crate::no_rounding() {
+            assert_eq!(Fix16(2).remap_precise((Fix16::ZERO, Fix16::from(3)), (Fix16::ZERO, Fix16::ONE)), Fix16(1));
+        }
+    }
+
+    #[test]
+    fn sin_cos() {
+        let quarter = Fix16::PI.0 >> 1;
+        let inputs = (i32::MIN..=i32::MAX).step_by(4099).chain(i32::MAX - quarter - 8..=i32::MAX);
+
+        for bits in inputs.chain(-0x8_0000..0x8_0000) {
+            let x = Fix16(bits);
+            assert_eq!(x.sin_cos(), (x.sin(), x.cos()), "{:?}", x);
+            assert_eq!(x.tan(), x.sin().saturating_div(x.cos()));
+        }
+
+        for bits in 0..=quarter {
+            let x = Fix16(bits);
+            assert_eq!(x.sin_unchecked(), x.sin(), "{:?}", x);
+            assert_eq!(x.cos_unchecked(), x.cos(), "{:?}", x);
+        }
+    }
+
+    #[test]
+    fn roots_and_reciprocals() {
+        use RoundingMode::*;
+
+        let inputs = (i32::MIN..=i32::MAX).step_by(8191).chain(-0x1000..0x1000);
+
+        for bits in inputs {
+            let (x, y) = (Fix16(bits), Fix16(bits.rotate_left(13)));
+            let value = f64::from(x);
+
+            let nearest = if crate::no_rounding() { TowardZero } else { NearestAway };
+            assert_eq!(x.recip(), x.recip_round(nearest), "{:?}", x);
+            assert_eq!(x.rsqrt(), x.rsqrt_round(nearest), "{:?}", x);
+            assert_eq!(x.cbrt(), x.cbrt_round(nearest), "{:?}", x);
+            assert_eq!(x.hypot(y), x.hypot_round(y, nearest), "{:?}", x);
+
+            // The correctly rounded results are within half an ulp.
+            let close = |res: Fix16, exact: f64| (f64::from(res) - exact).abs() * 65536.0 <= 0.5 + 1e-6;
+            if bits != 0 && (1.0 / value).abs() < 32767.0 {
+                assert!(close(x.recip_round(NearestEven), 1.0 / value), "{:?}", x);
+            }
+            if bits > 0 {
+                assert!(close(x.rsqrt_round(NearestEven), 1.0 / value.sqrt()), "{:?}", x);
+            }
+            assert!(close(x.cbrt_round(NearestEven), value.cbrt()), "{:?}", x);
+            if value.hypot(f64::from(y)) < 32767.0 {
+                assert!(close(x.hypot_round(y, NearestEven), value.hypot(f64::from(y))), "{:?}", x);
+            }
+
+            for &mode in &[TowardZero, Floor, Ceil] {
+                let (res, exact) = (x.cbrt_round(mode), value.cbrt());
+                assert!((f64::from(res) - exact).abs() < 1.0 / 65536.0, "{:?}", x);
+                assert!(match mode {
+                    Floor => f64::from(res) <= exact,
+                    Ceil => f64::from(res) >= exact,
+                    _ => f64::from(res).abs() <= exact.abs(),
+                });
+            }
+        }
+
+        assert_eq!(Fix16(0).recip(), Fix16::MAX);
+        assert_eq!(Fix16(1).recip(), Fix16::MAX);
+        assert_eq!(Fix16(-2).recip(), Fix16::MIN);
+        assert_eq!(Fix16(-2).recip_round(Floor), Fix16::MIN);
+        assert_eq!(Fix16::from(4).recip(), Fix16(0x4000));
+        assert_eq!(Fix16(0).rsqrt(), Fix16::MAX);
+        assert_eq!(Fix16(1).rsqrt(), Fix16::from(256));
+        assert_eq!(Fix16::from(-4).rsqrt(), Fix16(-0x8000));
+        assert_eq!(Fix16::from(-27).cbrt(), Fix16::from(-3));
+        assert_eq!(Fix16::MIN.cbrt(), Fix16::from(-32));
+        assert_eq!(Fix16::from(3).hypot(Fix16::from(-4)), Fix16::from(5));
+        assert_eq!(Fix16::from(30000).hypot(Fix16::from(-20000)), Fix16::MAX);
+        assert_eq!(Fix16::from(20000).hypot(Fix16::from(15000)), Fix16::from(25000));
+        assert_eq!(Fix16::MIN.hypot(Fix16(0)), Fix16::MAX);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn std_traits() {
+        use std::collections::{BTreeMap, HashSet};
+
+        assert_eq!(crate::fix16::FIX16_PI, Fix16::PI);
+        assert_eq!(crate::fix16::FIX16_E, Fix16::E);
+        assert_eq!(crate::fix16::FIX16_ONE, Fix16::ONE);
+        assert_eq!(crate::fix16::FIX16_MAX, Fix16::MAX);
+        assert_eq!(crate::fix16::FIX16_MIN, Fix16::MIN);
+        assert_eq!(Fixed::<29>::PI, Fixed::<29>(0x6487_ED51));
+        assert_eq!(Fixed::<4>::E, Fixed::<4>(43));
+        assert_eq!(Fix16::EPSILON.to_string(), "0.00002");
+        assert_eq!(Fix16::default(), Fix16::ZERO);
+
+        let mut values = vec![Fix16::ONE, -Fix16::PI, Fix16::ZERO, Fix16::MAX, Fix16::MIN];
+        values.sort();
+        assert_eq!(values, [Fix16::MIN, -Fix16::PI, Fix16::ZERO, Fix16::ONE, Fix16::MAX]);
+        assert!(Fix16(-1) < Fix16::ZERO && Fix16::E.max(Fix16::PI) == Fix16::PI);
+
+        let map: BTreeMap<_, _> = values.iter().map(|&val| (val, val.to_i32_round(RoundingMode::Floor))).collect();
+        assert_eq!(map[&-Fix16::PI], -4);
+        let set: HashSet<_> = values.iter().chain(&values).collect();
+        assert_eq!(set.len(), 5);
+
+        assert_eq!(Fix16(0x1_8000) & Fix16::ONE, Fix16::ONE);
+        assert_eq!(Fix16(0x1_0000) | Fix16(0x8000), Fix16(0x1_8000));
+        assert_eq!(Fix16(-1) ^ Fix16::MAX, Fix16::MIN);
+
+        let halves = [Fix16(0x8000); 5];
+        assert_eq!(halves.iter().sum::<Fix16>(), Fix16(0x2_8000));
+        assert_eq!(halves.iter().copied().product::<Fix16>(), Fix16(0x800));
+        assert_eq!(std::iter::empty::<Fix16>().product::<Fix16>(), Fix16::ONE);
+
+        let big = [Fix16::MAX, Fix16::ONE, -Fix16::ONE];
+        assert_eq!(big.iter().map(|&val| Saturating(val)).sum::<Saturating<_>>().0, Fix16(0x7FFE_FFFF));
+        assert_eq!(big.iter().map(|&val| Wrapping(val)).sum::<Wrapping<_>>().0, Fix16::MAX);
+        assert_eq!([Saturating(FIX32_MAX); 2].iter().copied().product::<Saturating<_>>().0, FIX32_MAX);
+        assert_eq!([Wrapping(UFIX16_ONE); 3].iter().copied().sum::<Wrapping<_>>().0, UFix16::from(3));
+    }
+
+    #[test]
+    fn fused_multiply_add() {
+        let ulp = Fix16(1);
+        let half = Fix16(0x8000);
+
+        // Each product is exactly half an ulp, so rounding them separately
+        // gives 0 or 2 ulps depending on the mode.
+        assert_eq!(Fix16::dot(&[ulp, ulp], &[half, half]), ulp);
+        assert_eq!(ulp.mul_add(half, ulp * half), ulp * half + ulp * half);
+        assert_ne!(Fix16::dot(&[ulp, ulp], &[half, half]), ulp * half + ulp * half);
+
+        let values = [Fix16::from(-3), Fix16(0x1_2345), Fix16(-0x8001), Fix16::PI, Fix16::E];
+        for &a in &values {
+            for &b in &values {
+                assert_eq!(a.mul_add(b, Fix16::ZERO), a * b);
+                assert_eq!(a.mul_add(b, Fix16::ONE), a * b + Fix16::ONE);
+                assert_eq!(Fix16::dot(&[a], &[b]), a * b);
+            }
+        }
+
+        // Intermediate overflow is fine as long as the result fits.
+        let two = Fix16::from(2);
+        assert_eq!(Fix16::dot(&[Fix16::MAX, Fix16::MAX], &[two, -two]), Fix16::ZERO);
+        assert_eq!(Fix16::MAX.mul_add(two, Fix16::MIN), Fix16::MAX - Fix16::EPSILON);
+        assert_eq!(Fix16::dot(&[], &[]), Fix16::ZERO);
+
+        assert_eq!(Fix16::dot(&[Fix16::MAX], &[two]), Fix16::MAX);
+        assert_eq!(Fix16::MIN.mul_add(two, Fix16::ZERO), Fix16::MIN);
+        assert_eq!(Fix16::overflowing_dot(&[Fix16::MAX], &[two]), Fix16::MAX.overflowing_mul(two));
+        assert_eq!(Fix16::overflowing_dot(&[Fix16::MAX, Fix16::MAX], &[two, -two]), (Fix16::ZERO, false));
+        assert_eq!(Fix16::MAX.overflowing_mul_add(Fix16::ONE, ulp), (Fix16::MIN, true));
+        assert_eq!(Fix16::MAX.checked_mul_add(Fix16::ONE, ulp), None);
+        assert_eq!(Fix16::checked_dot(&[two], &[two]), Some(Fix16::from(4)));
+    }
+
+    #[test]
+    #[should_panic(expected = "different lengths")]
+    fn dot_length_mismatch() {
+        Fix16::dot(&[Fix16::ONE], &[]);
     }
 }
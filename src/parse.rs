@@ -0,0 +1,205 @@
+use crate::fixed::ParseFixedError;
+
+// Everything here is a const fn so that literals can be converted at compile
+// time, which is why the loops are written out by hand and `?` is not used.
+
+// Parses an optionally signed number into the magnitude of a value with `frac`
+// fractional bits. A `0x`, `0o` or `0b` prefix after the sign selects
+// hexadecimal, octal or binary.
+//
+// The integer part saturates far above any supported range, so callers only
+// need to range check the returned magnitude.
+pub(crate) const fn parse_fixed(bytes: &[u8], frac: u32) -> Result<(bool, u128), ParseFixedError> {
+    let (negative, start) = sign(bytes);
+
+    let res = match radix_prefix(bytes, start) {
+        Some(radix) => parse_radix(bytes, start + 2, radix, frac),
+        None => parse_radix(bytes, start, 10, frac),
+    };
+
+    match res {
+        Ok(magnitude) => Ok((negative, magnitude)),
+        Err(err) => Err(err),
+    }
+}
+
+// Like parse_fixed, but always in `radix` with an optional matching prefix.
+pub(crate) const fn parse_radix_str(
+    bytes: &[u8],
+    radix: u32,
+    frac: u32,
+) -> Result<(bool, u128), ParseFixedError> {
+    assert!(
+        radix == 2 || radix == 8 || radix == 16,
+        "radix must be 2, 8 or 16"
+    );
+
+    let (negative, mut start) = sign(bytes);
+
+    if let Some(prefix) = radix_prefix(bytes, start) {
+        if prefix == radix {
+            start += 2;
+        }
+    }
+
+    match parse_radix(bytes, start, radix, frac) {
+        Ok(magnitude) => Ok((negative, magnitude)),
+        Err(err) => Err(err),
+    }
+}
+
+const fn sign(bytes: &[u8]) -> (bool, usize) {
+    match bytes.first() {
+        Some(b'-') => (true, 1),
+        Some(b'+') => (false, 1),
+        _ => (false, 0),
+    }
+}
+
+const fn radix_prefix(bytes: &[u8], start: usize) -> Option<u32> {
+    if bytes.len() < start + 2 || bytes[start] != b'0' {
+        return None;
+    }
+
+    match bytes[start + 1] {
+        b'x' | b'X' => Some(16),
+        b'o' | b'O' => Some(8),
+        b'b' | b'B' => Some(2),
+        _ => None,
+    }
+}
+
+const fn digit(bytes: &[u8], pos: usize, radix: u32) -> Result<u128, ParseFixedError> {
+    match (bytes[pos] as char).to_digit(radix) {
+        Some(d) => Ok(d as u128),
+        None => Err(ParseFixedError::InvalidDigit { pos }),
+    }
+}
+
+// Error positions are byte offsets into the whole input, sign and prefix
+// included.
+const fn parse_radix(
+    bytes: &[u8],
+    start: usize,
+    radix: u32,
+    frac: u32,
+) -> Result<u128, ParseFixedError> {
+    let mut int_end = start;
+    while int_end < bytes.len() && bytes[int_end] != b'.' {
+        int_end += 1;
+    }
+
+    let frac_start = if int_end < bytes.len() {
+        int_end + 1
+    } else {
+        int_end
+    };
+
+    if int_end == start && frac_start == bytes.len() {
+        return Err(ParseFixedError::Empty);
+    }
+
+    let mut int_part = 0u128;
+    let mut pos = start;
+    while pos < int_end {
+        int_part = match digit(bytes, pos, radix) {
+            Ok(d) => int_part * radix as u128 + d,
+            Err(err) => return Err(err),
+        };
+        if int_part > 1 << 64 {
+            int_part = 1 << 64;
+        }
+        pos += 1;
+    }
+
+    let frac_ulps = if radix == 10 {
+        decimal_frac_ulps(bytes, frac_start, frac)
+    } else {
+        pow2_frac_ulps(bytes, frac_start, radix.trailing_zeros() as usize, frac)
+    };
+
+    match frac_ulps {
+        Ok(frac_ulps) => Ok((int_part << frac) + frac_ulps),
+        Err(err) => Err(err),
+    }
+}
+
+// Rounds to nearest with ties away from zero, or truncates with the
+// `no-rounding` feature.
+const fn decimal_frac_ulps(bytes: &[u8], start: usize, frac: u32) -> Result<u128, ParseFixedError> {
+    // Every rounding midpoint is a multiple of 2^-(frac + 1), which has exactly
+    // frac + 1 decimal places. Truncating the input to that many places never
+    // moves it across a midpoint, so later digits are only validated.
+    let places = frac as usize + 1;
+    let mut frac_part = 0u128;
+    let mut pos = start;
+
+    while pos < bytes.len() {
+        let d = match digit(bytes, pos, 10) {
+            Ok(d) => d,
+            Err(err) => return Err(err),
+        };
+        if pos - start < places {
+            frac_part = frac_part * 10 + d;
+        }
+        pos += 1;
+    }
+
+    let mut count = bytes.len() - start;
+    while count < places {
+        frac_part *= 10;
+        count += 1;
+    }
+
+    Ok(frac_ulps(frac_part, frac))
+}
+
+// Fractions in a power of two radix are exact, so set bits below the last
+// fractional bit are rejected instead of rounded.
+const fn pow2_frac_ulps(
+    bytes: &[u8],
+    start: usize,
+    bits: usize,
+    frac: u32,
+) -> Result<u128, ParseFixedError> {
+    let frac = frac as usize;
+    let radix = 1 << bits;
+    let mut frac_bits = 0u128;
+    let mut pos = start;
+
+    while pos < bytes.len() {
+        let d = match digit(bytes, pos, radix) {
+            Ok(d) => d,
+            Err(err) => return Err(err),
+        };
+        let shift = bits * (pos - start + 1);
+
+        if shift <= frac {
+            frac_bits |= d << (frac - shift);
+        } else if shift - frac >= bits {
+            if d != 0 {
+                return Err(ParseFixedError::FractionTooLong);
+            }
+        } else if d & ((1 << (shift - frac)) - 1) != 0 {
+            return Err(ParseFixedError::FractionTooLong);
+        } else {
+            frac_bits |= d >> (shift - frac);
+        }
+
+        pos += 1;
+    }
+
+    Ok(frac_bits)
+}
+
+// Rounds a fraction given to frac + 1 decimal places to a whole number of ulps.
+pub(crate) const fn frac_ulps(frac_part: u128, frac: u32) -> u128 {
+    // frac_part / 10^(frac + 1) in units of half an ulp.
+    let half_ulps = frac_part / 5u128.pow(frac + 1);
+
+    if crate::no_rounding() {
+        half_ulps >> 1
+    } else {
+        (half_ulps + 1) >> 1
+    }
+}
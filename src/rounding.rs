@@ -0,0 +1,111 @@
+use core::cmp::Ordering;
+
+// How a result that falls between two representable values is rounded. The
+// operators round to nearest with ties away from zero, or towards negative
+// infinity when the `no-rounding` feature is enabled.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RoundingMode {
+    NearestAway,
+    NearestEven,
+    TowardZero,
+    Floor,
+    Ceil,
+}
+
+impl RoundingMode {
+    // Whether an inexact magnitude is rounded up, given how its discarded
+    // fraction compares to one half and whether the truncated value is odd.
+    fn round_up(self, negative: bool, half: Ordering, odd: bool) -> bool {
+        match self {
+            RoundingMode::NearestAway => half != Ordering::Less,
+            RoundingMode::NearestEven => {
+                half == Ordering::Greater || (half == Ordering::Equal && odd)
+            }
+            RoundingMode::TowardZero => false,
+            RoundingMode::Floor => negative,
+            RoundingMode::Ceil => !negative,
+        }
+    }
+}
+
+// The mode the multiplication operators round with.
+pub(crate) fn mul_mode() -> RoundingMode {
+    if crate::no_rounding() {
+        RoundingMode::Floor
+    } else {
+        RoundingMode::NearestAway
+    }
+}
+
+// The mode the division operators round with, which truncates instead of
+// flooring with the `no-rounding` feature.
+pub(crate) fn div_mode() -> RoundingMode {
+    if crate::no_rounding() {
+        RoundingMode::TowardZero
+    } else {
+        RoundingMode::NearestAway
+    }
+}
+
+// Divides by a positive `den`, rounding the exact quotient with `mode`.
+pub(crate) fn round_div(num: i128, den: i128, mode: RoundingMode) -> i128 {
+    let quot = num.abs() / den;
+    let rem = num.abs() % den;
+
+    let up = rem != 0 && mode.round_up(num < 0, (rem * 2).cmp(&den), quot & 1 != 0);
+    let res = if up { quot + 1 } else { quot };
+
+    if num < 0 {
+        -res
+    } else {
+        res
+    }
+}
+
+// Rounds the magnitude of a square root from its floor and the remainder
+// `num - floor * floor`. An integer's square root is never exactly halfway.
+pub(crate) fn round_sqrt(floor: u64, rem: u64, negative: bool, mode: RoundingMode) -> u64 {
+    let half = if rem > floor {
+        Ordering::Greater
+    } else {
+        Ordering::Less
+    };
+
+    if rem != 0 && mode.round_up(negative, half, floor & 1 != 0) {
+        floor + 1
+    } else {
+        floor
+    }
+}
+
+// Rounds an inexact magnitude up from `trunc` or not, given how the discarded
+// fraction compares to one half.
+pub(crate) fn round_inexact(trunc: u64, half: Ordering, negative: bool, mode: RoundingMode) -> u64 {
+    if mode.round_up(negative, half, trunc & 1 != 0) {
+        trunc + 1
+    } else {
+        trunc
+    }
+}
+
+// Rounds a float to an integer with `mode`, saturating to the i64 range and
+// mapping NaN to zero like `as` casts.
+#[cfg(feature = "float")]
+pub(crate) fn round_f32(val: f32, mode: RoundingMode) -> i64 {
+    let trunc = val as i64;
+    // Floats too large for this to be exact are integers already.
+    let frac = val - trunc as f32;
+    let frac = if frac < 0.0 { -frac } else { frac };
+
+    if frac == 0.0 || !frac.is_finite() || frac >= 1.0 {
+        return trunc;
+    }
+
+    let up = mode.round_up(val < 0.0, frac.partial_cmp(&0.5).unwrap(), trunc & 1 != 0);
+
+    match (up, val < 0.0) {
+        (false, _) => trunc,
+        (true, false) => trunc + 1,
+        (true, true) => trunc - 1,
+    }
+}
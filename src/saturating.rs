@@ -0,0 +1,107 @@
+use core::{iter, ops};
+
+use crate::fix32::{Fix32, FIX32_ONE};
+use crate::fixed::Fixed;
+use crate::ufix16::{UFix16, UFIX16_ONE};
+
+// Applies saturating semantics to every operator, like std::num::Saturating,
+// e.g. `(Saturating(a) * Saturating(b) + Saturating(c)).0`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Saturating<T>(pub T);
+
+macro_rules! saturating_impl {
+    ($([$($gen:tt)*] $t:ty, $zero:expr, $one:expr);*) => {$(
+        impl<$($gen)*> ops::Add for Saturating<$t> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                Saturating(self.0.saturating_add(rhs.0))
+            }
+        }
+
+        impl<$($gen)*> ops::AddAssign for Saturating<$t> {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl<$($gen)*> ops::Sub for Saturating<$t> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                Saturating(self.0.saturating_sub(rhs.0))
+            }
+        }
+
+        impl<$($gen)*> ops::SubAssign for Saturating<$t> {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl<$($gen)*> ops::Mul for Saturating<$t> {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self::Output {
+                Saturating(self.0.saturating_mul(rhs.0))
+            }
+        }
+
+        impl<$($gen)*> ops::MulAssign for Saturating<$t> {
+            fn mul_assign(&mut self, rhs: Self) {
+                *self = *self * rhs;
+            }
+        }
+
+        // Division by zero saturates towards the sign of the dividend.
+        impl<$($gen)*> ops::Div for Saturating<$t> {
+            type Output = Self;
+
+            fn div(self, rhs: Self) -> Self::Output {
+                Saturating(self.0.saturating_div(rhs.0))
+            }
+        }
+
+        impl<$($gen)*> ops::DivAssign for Saturating<$t> {
+            fn div_assign(&mut self, rhs: Self) {
+                *self = *self / rhs;
+            }
+        }
+
+        // The remainder can never overflow, but like the integer types it
+        // panics on a zero divisor.
+        impl<$($gen)*> ops::Rem for Saturating<$t> {
+            type Output = Self;
+
+            fn rem(self, rhs: Self) -> Self::Output {
+                let mut res = self.0;
+                res.0 = res.0.wrapping_rem(rhs.0 .0);
+                Saturating(res)
+            }
+        }
+
+        impl<$($gen)*> ops::RemAssign for Saturating<$t> {
+            fn rem_assign(&mut self, rhs: Self) {
+                *self = *self % rhs;
+            }
+        }
+
+        impl<$($gen)*> iter::Sum for Saturating<$t> {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Saturating($zero), |acc, val| acc + val)
+            }
+        }
+
+        impl<$($gen)*> iter::Product for Saturating<$t> {
+            fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Saturating($one), |acc, val| acc * val)
+            }
+        }
+    )*};
+}
+
+saturating_impl! {
+    [const FRAC: u32] Fixed<FRAC>, Fixed::ZERO, Fixed::ONE;
+    [] Fix32, Fix32(0), FIX32_ONE;
+    [] UFix16, UFix16(0), UFIX16_ONE
+}
@@ -0,0 +1,305 @@
+use core::{convert::TryFrom, fmt, ops};
+
+use crate::fix16::Fix16;
+use crate::fixed::{sqrt_q16, TryFromFixedError};
+
+#[derive(Copy, Clone, Debug)]
+pub struct UFix16(pub u32);
+
+pub const UFIX16_MAX: UFix16 = UFix16(0xFFFFFFFF);
+pub const UFIX16_MIN: UFix16 = UFix16(0);
+pub const UFIX16_OVERFLOW: UFix16 = UFIX16_MAX;
+
+pub const UFIX16_PI: UFix16 = UFix16(205887);
+pub const UFIX16_E: UFix16 = UFix16(178145);
+pub const UFIX16_ONE: UFix16 = UFix16(0x00010000);
+
+impl fmt::Display for UFix16 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (int_part, frac_bits) = (self.0 >> 16, self.0 & 0xFFFF);
+        crate::format::fmt_decimal(f, false, int_part as u64, frac_bits as u64, 16)
+    }
+}
+
+crate::format::radix_fmt_impls!([] UFix16, |val: UFix16| {
+    (false, (val.0 >> 16) as u64, (val.0 & 0xFFFF) as u64, 16)
+});
+
+impl From<u32> for UFix16 {
+    fn from(val: u32) -> Self {
+        UFix16(val * UFIX16_ONE.0)
+    }
+}
+
+#[cfg(feature = "float")]
+impl From<f32> for UFix16 {
+    fn from(val: f32) -> Self {
+        UFix16({
+            let mut x = val * UFIX16_ONE.0 as f32;
+
+            if !crate::no_rounding() {
+                x += 0.5;
+            }
+
+            x as u32
+        })
+    }
+}
+
+impl From<UFix16> for u32 {
+    fn from(val: UFix16) -> Self {
+        if crate::no_rounding() {
+            val.0 >> 16
+        } else {
+            ((val.0 as u64 + (UFIX16_ONE.0 >> 1) as u64) >> 16) as u32
+        }
+    }
+}
+
+#[cfg(feature = "float")]
+impl From<UFix16> for f32 {
+    fn from(val: UFix16) -> Self {
+        val.0 as f32 / UFIX16_ONE.0 as f32
+    }
+}
+
+impl TryFrom<Fix16> for UFix16 {
+    type Error = TryFromFixedError;
+
+    fn try_from(val: Fix16) -> Result<Self, Self::Error> {
+        if val.0 < 0 {
+            Err(TryFromFixedError(()))
+        } else {
+            Ok(UFix16(val.0 as u32))
+        }
+    }
+}
+
+impl TryFrom<UFix16> for Fix16 {
+    type Error = TryFromFixedError;
+
+    fn try_from(val: UFix16) -> Result<Self, Self::Error> {
+        if val.0 > i32::MAX as u32 {
+            Err(TryFromFixedError(()))
+        } else {
+            Ok(Fix16(val.0 as i32))
+        }
+    }
+}
+
+impl UFix16 {
+    pub fn floor(self) -> UFix16 {
+        UFix16(self.0 & !0xFFFF)
+    }
+
+    pub fn ceil(self) -> UFix16 {
+        UFix16(
+            (self.0 & !0xFFFF)
+                + if (self.0 & 0xFFFF) != 0 {
+                    UFIX16_ONE.0
+                } else {
+                    0
+                },
+        )
+    }
+
+    pub fn min(self, rhs: UFix16) -> UFix16 {
+        if self.0 <= rhs.0 {
+            self
+        } else {
+            rhs
+        }
+    }
+
+    pub fn max(self, rhs: UFix16) -> UFix16 {
+        if self.0 >= rhs.0 {
+            self
+        } else {
+            rhs
+        }
+    }
+
+    pub fn clamp(self, low: UFix16, high: UFix16) -> UFix16 {
+        self.min(low).max(high)
+    }
+
+    pub fn overflowing_add(self, rhs: UFix16) -> (UFix16, bool) {
+        let res = self.0.overflowing_add(rhs.0);
+        (UFix16(res.0), res.1)
+    }
+
+    pub fn overflowing_sub(self, rhs: UFix16) -> (UFix16, bool) {
+        let res = self.0.overflowing_sub(rhs.0);
+        (UFix16(res.0), res.1)
+    }
+
+    pub fn overflowing_mul(self, rhs: UFix16) -> (UFix16, bool) {
+        let product = self.0 as u64 * rhs.0 as u64;
+
+        let mut res = product >> 16;
+        if !crate::no_rounding() {
+            res += (product >> 15) & 1;
+        }
+
+        (UFix16(res as u32), res > u32::MAX as u64)
+    }
+
+    pub fn overflowing_div(self, rhs: UFix16) -> (UFix16, bool) {
+        // Division by zero is reported as an overflow.
+        if rhs.0 == 0 {
+            return (UFIX16_OVERFLOW, true);
+        }
+
+        // libfixmath's division loop assumes operands below 2^31, so divide
+        // directly instead, computing one extra bit of the quotient to round
+        // with.
+        let mut quotient = ((self.0 as u64) << 17) / rhs.0 as u64;
+
+        if !crate::no_rounding() {
+            quotient += 1;
+        }
+
+        let res = quotient >> 1;
+        (UFix16(res as u32), res > u32::MAX as u64)
+    }
+
+    pub fn saturating_add(self, rhs: UFix16) -> UFix16 {
+        UFix16(self.0.saturating_add(rhs.0))
+    }
+
+    pub fn saturating_sub(self, rhs: UFix16) -> UFix16 {
+        UFix16(self.0.saturating_sub(rhs.0))
+    }
+
+    pub fn saturating_mul(self, rhs: UFix16) -> UFix16 {
+        match self.overflowing_mul(rhs) {
+            (_, true) => UFIX16_MAX,
+            (res, false) => res,
+        }
+    }
+
+    pub fn saturating_div(self, rhs: UFix16) -> UFix16 {
+        match self.overflowing_div(rhs) {
+            (_, true) => UFIX16_MAX,
+            (res, false) => res,
+        }
+    }
+
+    pub fn sqrt(self) -> UFix16 {
+        UFix16(sqrt_q16(self.0))
+    }
+
+    // Writes the Display form into `buf` without allocating.
+    pub fn format_into(self, buf: &mut [u8]) -> Result<&str, fmt::Error> {
+        crate::format::format_into(buf, format_args!("{}", self))
+    }
+}
+
+impl ops::Add for UFix16 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        crate::operator_result(self.overflowing_add(rhs), UFIX16_OVERFLOW, "add")
+    }
+}
+
+impl ops::AddAssign for UFix16 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl ops::Sub for UFix16 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        crate::operator_result(self.overflowing_sub(rhs), UFIX16_OVERFLOW, "subtract")
+    }
+}
+
+impl ops::SubAssign for UFix16 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl ops::Mul for UFix16 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        crate::operator_result(self.overflowing_mul(rhs), UFIX16_OVERFLOW, "multiply")
+    }
+}
+
+impl ops::MulAssign for UFix16 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl ops::Div for UFix16 {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        crate::check_divisor(rhs.0 == 0, "divide");
+        crate::operator_result(self.overflowing_div(rhs), UFIX16_OVERFLOW, "divide")
+    }
+}
+
+impl ops::DivAssign for UFix16 {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl ops::Rem for UFix16 {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        crate::check_divisor(rhs.0 == 0, "calculate the remainder");
+        match self.0.checked_rem(rhs.0) {
+            Some(res) => UFix16(res),
+            None => UFIX16_OVERFLOW,
+        }
+    }
+}
+
+impl ops::RemAssign for UFix16 {
+    fn rem_assign(&mut self, rhs: Self) {
+        *self = *self % rhs;
+    }
+}
+
+impl ops::Shl for UFix16 {
+    type Output = Self;
+
+    fn shl(self, rhs: Self) -> Self::Output {
+        UFix16(self.0 << u32::from(rhs))
+    }
+}
+
+impl ops::ShlAssign for UFix16 {
+    fn shl_assign(&mut self, rhs: Self) {
+        *self = *self << rhs;
+    }
+}
+
+impl ops::Shr for UFix16 {
+    type Output = Self;
+
+    fn shr(self, rhs: Self) -> Self::Output {
+        UFix16(self.0 >> u32::from(rhs))
+    }
+}
+
+impl ops::ShrAssign for UFix16 {
+    fn shr_assign(&mut self, rhs: Self) {
+        *self = *self >> rhs;
+    }
+}
+
+impl PartialEq for UFix16 {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
@@ -0,0 +1,104 @@
+use core::{iter, ops};
+
+use crate::fix32::{Fix32, FIX32_ONE};
+use crate::fixed::Fixed;
+use crate::ufix16::{UFix16, UFIX16_ONE};
+
+// Applies wrapping semantics to every operator, like std::num::Wrapping. The
+// results are the values returned by the `overflowing_*` methods.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Wrapping<T>(pub T);
+
+macro_rules! wrapping_impl {
+    ($([$($gen:tt)*] $t:ty, $zero:expr, $one:expr);*) => {$(
+        impl<$($gen)*> ops::Add for Wrapping<$t> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                Wrapping(self.0.overflowing_add(rhs.0).0)
+            }
+        }
+
+        impl<$($gen)*> ops::AddAssign for Wrapping<$t> {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl<$($gen)*> ops::Sub for Wrapping<$t> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                Wrapping(self.0.overflowing_sub(rhs.0).0)
+            }
+        }
+
+        impl<$($gen)*> ops::SubAssign for Wrapping<$t> {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl<$($gen)*> ops::Mul for Wrapping<$t> {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self::Output {
+                Wrapping(self.0.overflowing_mul(rhs.0).0)
+            }
+        }
+
+        impl<$($gen)*> ops::MulAssign for Wrapping<$t> {
+            fn mul_assign(&mut self, rhs: Self) {
+                *self = *self * rhs;
+            }
+        }
+
+        impl<$($gen)*> ops::Div for Wrapping<$t> {
+            type Output = Self;
+
+            fn div(self, rhs: Self) -> Self::Output {
+                Wrapping(self.0.overflowing_div(rhs.0).0)
+            }
+        }
+
+        impl<$($gen)*> ops::DivAssign for Wrapping<$t> {
+            fn div_assign(&mut self, rhs: Self) {
+                *self = *self / rhs;
+            }
+        }
+
+        impl<$($gen)*> ops::Rem for Wrapping<$t> {
+            type Output = Self;
+
+            fn rem(self, rhs: Self) -> Self::Output {
+                let mut res = self.0;
+                res.0 = res.0.wrapping_rem(rhs.0 .0);
+                Wrapping(res)
+            }
+        }
+
+        impl<$($gen)*> ops::RemAssign for Wrapping<$t> {
+            fn rem_assign(&mut self, rhs: Self) {
+                *self = *self % rhs;
+            }
+        }
+
+        impl<$($gen)*> iter::Sum for Wrapping<$t> {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Wrapping($zero), |acc, val| acc + val)
+            }
+        }
+
+        impl<$($gen)*> iter::Product for Wrapping<$t> {
+            fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Wrapping($one), |acc, val| acc * val)
+            }
+        }
+    )*};
+}
+
+wrapping_impl! {
+    [const FRAC: u32] Fixed<FRAC>, Fixed::ZERO, Fixed::ONE;
+    [] Fix32, Fix32(0), FIX32_ONE;
+    [] UFix16, UFix16(0), UFIX16_ONE
+}